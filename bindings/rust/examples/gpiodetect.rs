@@ -24,14 +24,8 @@ fn main() {
 
         if gpiod_is_gpiochip_device(path) {
             let chip = Chip::open(path).unwrap();
-            let ngpio = chip.get_num_lines();
 
-            println!(
-                "{} [{}] ({})",
-                chip.get_name().unwrap(),
-                chip.get_label().unwrap(),
-                ngpio
-            );
+            println!("{}", chip);
         }
     }
 }
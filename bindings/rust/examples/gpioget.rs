@@ -7,7 +7,7 @@
 
 use std::env;
 
-use libgpiod::{Chip, Direction, LineConfig, RequestConfig};
+use libgpiod::{parse_offset_value, Chip, Direction, LineConfig, RequestConfig};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -20,7 +20,17 @@ fn main() {
     let mut offsets = Vec::<u32>::new();
 
     for arg in &args[2..] {
-        let offset = arg.parse::<u32>().unwrap();
+        let offset = match parse_offset_value(arg) {
+            Ok((offset, None)) => offset,
+            Ok((_, Some(_))) => {
+                println!("Unexpected value in line argument: {}", arg);
+                return;
+            }
+            Err(err) => {
+                println!("{}: {}", err, arg);
+                return;
+            }
+        };
 
         offsets.push(offset);
         config.set_direction_override(Direction::Input, offset);
@@ -9,42 +9,12 @@ use std::env;
 use std::fs;
 use std::path::Path;
 
-use libgpiod::{gpiod_is_gpiochip_device, Chip, Direction};
+use libgpiod::{gpiod_is_gpiochip_device, Chip};
 
 fn line_info(chip: &Chip, offset: u32) {
     let info = chip.line_info(offset).unwrap();
-    let off = info.get_offset();
 
-    let name = match info.get_name() {
-        Ok(name) => name,
-        _ => "unused",
-    };
-
-    let consumer = match info.get_consumer() {
-        Ok(name) => name,
-        _ => "unnamed",
-    };
-
-    let low = if info.is_active_low() {
-        "active-low"
-    } else {
-        "active-high"
-    };
-
-    let dir = match info.get_direction().unwrap() {
-        Direction::AsIs => "None",
-        Direction::Input => "Input",
-        Direction::Output => "Output",
-    };
-
-    println!(
-        "\tline {:>3}\
-              \t{:>10}\
-              \t{:>10}\
-              \t{:>6}\
-              \t{:>14}",
-        off, name, consumer, dir, low
-    );
+    println!("\t{}", info.summary());
 }
 
 fn chip_info(path: &str) {
@@ -8,7 +8,7 @@
 use std::env;
 use std::time::Duration;
 
-use libgpiod::{Chip, Edge, EdgeEventBuffer, Error, LineConfig, LineEdgeEvent, RequestConfig};
+use libgpiod::{Chip, Edge, EdgeEventBuffer, LineConfig, LineEdgeEvent, RequestConfig};
 
 fn usage(name: &str) {
     println!("Usage: {} <chip> <offset0> ...", name);
@@ -42,13 +42,13 @@ fn main() {
     let request = chip.request_lines(&rconfig, &config).unwrap();
 
     loop {
-        match request.wait_edge_event(Duration::new(1, 0)) {
-            Err(Error::OperationTimedOut) => continue,
+        match request.poll_edge_event(Duration::new(1, 0)) {
             Err(x) => {
                 println!("{:?}", x);
                 return;
             }
-            Ok(()) => (),
+            Ok(false) => continue,
+            Ok(true) => (),
         }
 
         let count = request.read_edge_event(&buffer, 1).unwrap();
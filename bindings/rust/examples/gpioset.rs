@@ -5,9 +5,10 @@
 //
 // Simplified Rust implementation of the gpioset tool.
 
+use std::convert::TryFrom;
 use std::env;
 
-use libgpiod::{Chip, Direction, LineConfig, RequestConfig};
+use libgpiod::{parse_offset_value, Chip, Direction, LineConfig, LineValue, RequestConfig};
 
 fn usage(name: &str) {
     println!("Usage: {} <chip> <line_offset0>=<value0> ...", name);
@@ -25,17 +26,30 @@ fn main() {
     let mut values = Vec::<i32>::new();
 
     for arg in &args[2..] {
-        let pair: Vec<&str> = arg.split('=').collect();
-        if pair.len() != 2 {
-            usage(&args[0]);
-            return;
-        }
+        let (offset, value) = match parse_offset_value(arg) {
+            Ok((offset, Some(value))) => (offset, value),
+            Ok((_, None)) => {
+                usage(&args[0]);
+                return;
+            }
+            Err(err) => {
+                println!("{}: {}", err, arg);
+                usage(&args[0]);
+                return;
+            }
+        };
 
-        let offset = pair[0].parse::<u32>().unwrap();
-        let value = pair[1].parse::<u32>().unwrap();
+        let value = match u32::try_from(value).map(LineValue::try_from) {
+            Ok(Ok(value)) => value,
+            _ => {
+                println!("Invalid line value: {}", value);
+                usage(&args[0]);
+                return;
+            }
+        };
 
         offsets.push(offset);
-        values.push(value as i32);
+        values.push(i32::from(value));
     }
 
     config.set_direction_default(Direction::Output);
@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+//! async-io integration
+//!
+//! Allows consuming the edge events of a line request as an asynchronous
+//! stream, with executors built on async-io such as smol and async-std.
+
+use libc::{EAGAIN, EIO};
+use std::os::unix::io::RawFd;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use ::async_io::Async;
+use futures_core::Stream;
+use vmm_sys_util::errno::Error as IoError;
+
+use super::{EdgeEvent, EdgeEventBuffer, Error, LineRequest, RequestFd, Result};
+
+/// Edge events stream
+///
+/// A stream owning a line request and yielding copies of its edge events.
+/// Events are read in batches into an internal buffer, which is refilled once
+/// all the events it holds were yielded.
+///
+/// The file descriptor of the request is switched to non-blocking mode, reads
+/// don't block even if no event is pending.
+pub struct EdgeEventStream {
+    // Dropped first, to stop watching the file descriptor before it's closed.
+    fd: Async<RequestFd>,
+    request: LineRequest,
+    buffer: EdgeEventBuffer,
+    index: u32,
+    count: u32,
+}
+
+impl EdgeEventStream {
+    /// Create a new edge events stream for the line request.
+    pub fn new(request: LineRequest) -> Result<Self> {
+        let buffer = EdgeEventBuffer::new(0)?;
+        let fd = Async::new(RequestFd(request.get_fd() as RawFd)).map_err(|err| {
+            Error::OperationFailed(
+                "Gpio EdgeEventStream new",
+                IoError::new(err.raw_os_error().unwrap_or(EIO)),
+            )
+        })?;
+
+        Ok(Self {
+            fd,
+            request,
+            buffer,
+            index: 0,
+            count: 0,
+        })
+    }
+
+    /// Get the line request associated with the stream.
+    pub fn request(&self) -> &LineRequest {
+        &self.request
+    }
+}
+
+impl Stream for EdgeEventStream {
+    type Item = Result<EdgeEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let stream = self.get_mut();
+
+        loop {
+            if stream.index < stream.count {
+                let event = stream.buffer.get_event_copy(stream.index as u64);
+
+                stream.index += 1;
+                return Poll::Ready(Some(event));
+            }
+
+            match stream.fd.poll_readable(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(err)) => {
+                    return Poll::Ready(Some(Err(Error::OperationFailed(
+                        "Gpio EdgeEventStream poll",
+                        IoError::new(err.raw_os_error().unwrap_or(EIO)),
+                    ))))
+                }
+                Poll::Ready(Ok(())) => (),
+            }
+
+            match stream
+                .request
+                .read_edge_event(&stream.buffer, stream.buffer.get_capacity())
+            {
+                Ok(count) => {
+                    stream.index = 0;
+                    stream.count = count;
+                }
+                // Spurious wakeup, wait for the file descriptor again.
+                Err(Error::OperationFailed(_, err)) if err.errno() == EAGAIN => (),
+                Err(err) => return Poll::Ready(Some(Err(err))),
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+//! calloop integration
+//!
+//! Allows inserting a line request into a calloop event loop as a source of
+//! edge events.
+
+use std::os::unix::io::RawFd;
+
+use ::calloop::generic::Generic;
+use ::calloop::{EventSource, Interest, Mode, Poll, PostAction, Readiness, Token, TokenFactory};
+
+use super::{EdgeEvent, EdgeEventBuffer, Error, LineRequest, RequestFd, Result};
+
+/// Edge events source
+///
+/// An event source for calloop, owning a line request and dispatching copies
+/// of its edge events to the callback. The request's file descriptor is
+/// polled in level-triggered mode, if more events are pending than fit in the
+/// internal buffer, the source fires again on the next dispatch.
+pub struct EdgeEventSource {
+    request: LineRequest,
+    buffer: EdgeEventBuffer,
+    source: Generic<RequestFd, Error>,
+}
+
+impl EdgeEventSource {
+    /// Create a new edge events source for the line request.
+    pub fn new(request: LineRequest) -> Result<Self> {
+        let buffer = EdgeEventBuffer::new(0)?;
+        let fd = RequestFd(request.get_fd() as RawFd);
+
+        Ok(Self {
+            request,
+            buffer,
+            source: Generic::new_with_error(fd, Interest::READ, Mode::Level),
+        })
+    }
+
+    /// Get the line request associated with the source.
+    pub fn request(&self) -> &LineRequest {
+        &self.request
+    }
+}
+
+impl EventSource for EdgeEventSource {
+    type Event = EdgeEvent;
+    type Metadata = ();
+    type Ret = ();
+    type Error = Error;
+
+    fn process_events<F>(
+        &mut self,
+        readiness: Readiness,
+        token: Token,
+        mut callback: F,
+    ) -> Result<PostAction>
+    where
+        F: FnMut(Self::Event, &mut Self::Metadata) -> Self::Ret,
+    {
+        let request = &self.request;
+        let buffer = &self.buffer;
+
+        self.source.process_events(readiness, token, |_, _| {
+            let count = request.read_edge_event(buffer, buffer.get_capacity())?;
+
+            for index in 0..count {
+                callback(buffer.get_event_copy(index as u64)?, &mut ());
+            }
+
+            Ok(PostAction::Continue)
+        })
+    }
+
+    fn register(
+        &mut self,
+        poll: &mut Poll,
+        token_factory: &mut TokenFactory,
+    ) -> ::calloop::Result<()> {
+        self.source.register(poll, token_factory)
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &mut Poll,
+        token_factory: &mut TokenFactory,
+    ) -> ::calloop::Result<()> {
+        self.source.reregister(poll, token_factory)
+    }
+
+    fn unregister(&mut self, poll: &mut Poll) -> ::calloop::Result<()> {
+        self.source.unregister(poll)
+    }
+}
@@ -3,16 +3,26 @@
 // Copyright 2022 Linaro Ltd. All Rights Reserved.
 //     Viresh Kumar <viresh.kumar@linaro.org>
 
+use libc::{EBUSY, EIO, ENOENT};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs;
+use std::hash::{Hash, Hasher};
 use std::os::raw::c_char;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::thread::sleep;
 use std::time::Duration;
-use std::{slice, str};
+use std::{fmt, slice, str};
 
 use vmm_sys_util::errno::Error as IoError;
 
 use super::{
-    bindings, chip_info::ChipInfo, Error, InfoEvent, LineConfig, LineInfo, LineRequest,
-    RequestConfig, Result,
+    bindings, chip_info::ChipInfo, duration_to_timeout_ns, gpiod_is_gpiochip_device, Drive, Edge,
+    Error, InfoEvent, LineConfig, LineInfo, LineRequest, Offset, RequestBuilder, RequestConfig,
+    Result, WatchGuard,
 };
 
 /// GPIO chip
@@ -34,7 +44,7 @@ impl ChipInternal {
 
         let chip = unsafe { bindings::gpiod_chip_open(path.as_ptr() as *const c_char) };
         if chip.is_null() {
-            return Err(Error::OperationFailed("Gpio Chip open", IoError::last()));
+            return Err(Error::op_failed("Gpio Chip open", IoError::last()));
         }
 
         Ok(Self { chip })
@@ -47,7 +57,8 @@ impl ChipInternal {
 }
 
 impl Drop for ChipInternal {
-    /// Close the chip and release all associated resources.
+    /// Close the chip and release all associated resources, once the last
+    /// chip, line request or watching line info referring to it is dropped.
     fn drop(&mut self) {
         unsafe { bindings::gpiod_chip_close(self.chip) }
     }
@@ -71,17 +82,116 @@ impl Chip {
         Ok(Self { ichip, info })
     }
 
+    /// Find a chip by path, checking that the path is a GPIO chip first.
+    ///
+    /// Same as `open()`, but returns `Error::NotAGpioChip` for existing files
+    /// which aren't GPIO chip character devices, instead of the errno the
+    /// kernel reports for them.
+    pub fn open_checked(path: &str) -> Result<Self> {
+        if Path::new(path).exists() && !gpiod_is_gpiochip_device(path) {
+            return Err(Error::NotAGpioChip);
+        }
+
+        Self::open(path)
+    }
+
+    /// Find the GPIO chips whose label contains the pattern.
+    ///
+    /// Opens every GPIO chip character device named `gpiochip*` in `/dev`,
+    /// the matching chips are returned sorted by path. Chips which can't be
+    /// opened, e.g. for lack of permissions, are skipped.
+    pub fn find_by_label_pattern(pattern: &str) -> Result<Vec<Self>> {
+        let entries = fs::read_dir("/dev").map_err(|err| {
+            Error::OperationFailed(
+                "Gpio Chip find-by-label",
+                IoError::new(err.raw_os_error().unwrap_or(EIO)),
+            )
+        })?;
+
+        let mut chips = Vec::new();
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let path = match path.to_str() {
+                Some(path) => path,
+                None => continue,
+            };
+
+            if !entry.file_name().as_bytes().starts_with(b"gpiochip")
+                || !gpiod_is_gpiochip_device(path)
+            {
+                continue;
+            }
+
+            if let Ok(chip) = Self::open(path) {
+                if matches!(chip.get_label(), Ok(label) if label.contains(pattern)) {
+                    chips.push(chip);
+                }
+            }
+        }
+
+        chips.sort();
+        Ok(chips)
+    }
+
+    /// Reopen the chip using the path it was originally found by.
+    ///
+    /// This replaces the underlying file descriptor and chip information, and
+    /// allows recovering after the device was removed and has reappeared. Line
+    /// requests and watched lines made with the old descriptor aren't carried
+    /// over.
+    pub fn reopen(&mut self) -> Result<()> {
+        let path = self.get_path()?.to_owned();
+
+        *self = Self::open(&path)?;
+        Ok(())
+    }
+
+    /// Close the chip and release all associated resources.
+    ///
+    /// This is equivalent to dropping the chip, and makes early release
+    /// explicit at the call site. Line requests made on the chip and line info
+    /// objects watching its lines keep a reference to it: if any of them are
+    /// still around, the file descriptor is only closed once the last of them
+    /// is dropped.
+    pub fn close(self) {
+        let Self { ichip, info } = self;
+
+        drop(info);
+        drop(ichip);
+    }
+
+    /// Get the underlying libgpiod chip object.
+    ///
+    /// # Safety
+    ///
+    /// This is an escape hatch for calling libgpiod functions not wrapped by
+    /// this crate. The pointer is owned by the `Chip`, it must not be freed or
+    /// closed by the caller and must not be used after the `Chip` is dropped.
+    pub unsafe fn as_raw_ptr(&self) -> *mut bindings::gpiod_chip {
+        self.ichip.chip()
+    }
+
     /// Get the chip name as represented in the kernel.
+    ///
+    /// Reads the information cached when the chip was opened, no calls are
+    /// made to the kernel.
     pub fn get_name(&self) -> Result<&str> {
         self.info.name()
     }
 
     /// Get the chip label as represented in the kernel.
+    ///
+    /// Reads the information cached when the chip was opened, no calls are
+    /// made to the kernel.
     pub fn get_label(&self) -> Result<&str> {
         self.info.label()
     }
 
     /// Get the number of GPIO lines exposed by the chip.
+    ///
+    /// Reads the information cached when the chip was opened, this is cheap
+    /// and doesn't allocate.
     pub fn get_num_lines(&self) -> u32 {
         self.info.num_lines()
     }
@@ -99,14 +209,86 @@ impl Chip {
         .map_err(Error::InvalidString)
     }
 
+    /// Get the path used to find the chip as an owned `PathBuf`.
+    ///
+    /// Unlike `get_path()`, this doesn't fail for paths which aren't valid UTF-8.
+    pub fn get_path_buf(&self) -> Result<PathBuf> {
+        let path = self.path_bytes();
+        if path.is_empty() {
+            return Err(Error::NameNotFound("GPIO chip's path"));
+        }
+
+        Ok(PathBuf::from(OsStr::from_bytes(path)))
+    }
+
+    /// Private helper, Returns the raw bytes of the path used to find the chip.
+    fn path_bytes(&self) -> &[u8] {
+        // SAFETY: The string returned by libgpiod is guaranteed to live as long
+        // as the `struct Chip`.
+        let path = unsafe { bindings::gpiod_chip_get_path(self.ichip.chip()) };
+        if path.is_null() {
+            return &[];
+        }
+
+        // SAFETY: The string is guaranteed to be valid here.
+        unsafe { slice::from_raw_parts(path as *const u8, bindings::strlen(path) as usize) }
+    }
+
     /// Get information about the chip.
+    ///
+    /// Unlike the `get_name()`, `get_label()` and `get_num_lines()` accessors,
+    /// which use the information cached when the chip was opened, this
+    /// retrieves and allocates a fresh copy from the kernel on every call.
     pub fn info(&self) -> Result<ChipInfo> {
         ChipInfo::new(self.ichip.clone())
     }
 
     /// Get a snapshot of information about the line.
-    pub fn line_info(&self, offset: u32) -> Result<LineInfo> {
-        LineInfo::new(self.ichip.clone(), offset, false)
+    pub fn line_info(&self, offset: impl Into<Offset>) -> Result<LineInfo> {
+        LineInfo::new(self.ichip.clone(), offset.into().0, false)
+    }
+
+    /// Returns true if the line at given offset is in use, either requested by
+    /// a user or hogged by the kernel.
+    ///
+    /// The line info is still retrieved from the kernel, but it is released
+    /// right away.
+    pub fn is_line_used(&self, offset: u32) -> Result<bool> {
+        Ok(self.line_info(offset)?.is_used())
+    }
+
+    /// Get snapshots of information about a subset of lines.
+    ///
+    /// Stops at, and returns, the first error encountered.
+    pub fn line_infos_for(&self, offsets: &[u32]) -> Result<Vec<LineInfo>> {
+        offsets
+            .iter()
+            .map(|offset| self.line_info(*offset))
+            .collect()
+    }
+
+    /// Get the offsets of lines currently in use, either requested by a user
+    /// or hogged by the kernel.
+    pub fn used_lines(&self) -> Result<Vec<u32>> {
+        self.lines_by_usage(true)
+    }
+
+    /// Get the offsets of lines currently not in use.
+    pub fn free_lines(&self) -> Result<Vec<u32>> {
+        self.lines_by_usage(false)
+    }
+
+    /// Private helper, Returns offsets of lines matching the usage.
+    fn lines_by_usage(&self, used: bool) -> Result<Vec<u32>> {
+        let mut offsets = Vec::new();
+
+        for offset in 0..self.get_num_lines() {
+            if self.is_line_used(offset)? == used {
+                offsets.push(offset);
+            }
+        }
+
+        Ok(offsets)
     }
 
     /// Get the current snapshot of information about the line at given offset
@@ -115,6 +297,12 @@ impl Chip {
         LineInfo::new(self.ichip.clone(), offset, true)
     }
 
+    /// Start watching a set of lines for changes, until the returned guard is
+    /// dropped.
+    pub fn watch_guard(&self, offsets: &[u32]) -> Result<WatchGuard> {
+        WatchGuard::new(self, offsets)
+    }
+
     /// Get the file descriptor associated with the chip.
     ///
     /// The returned file descriptor must not be closed by the caller, else other methods for the
@@ -132,11 +320,11 @@ impl Chip {
     /// Wait for line status events on any of the watched lines on the chip.
     pub fn wait_info_event(&self, timeout: Duration) -> Result<()> {
         let ret = unsafe {
-            bindings::gpiod_chip_wait_info_event(self.ichip.chip(), timeout.as_nanos() as i64)
+            bindings::gpiod_chip_wait_info_event(self.ichip.chip(), duration_to_timeout_ns(timeout))
         };
 
         match ret {
-            -1 => Err(Error::OperationFailed(
+            -1 => Err(Error::device_op_failed(
                 "Gpio Chip info-event-wait",
                 IoError::last(),
             )),
@@ -145,6 +333,18 @@ impl Chip {
         }
     }
 
+    /// Poll for line status events on any of the watched lines on the chip.
+    ///
+    /// Same as `wait_info_event()`, but a timeout isn't an error: returns true
+    /// if events are pending, false if the timeout expired.
+    pub fn poll_info_event(&self, timeout: Duration) -> Result<bool> {
+        match self.wait_info_event(timeout) {
+            Ok(()) => Ok(true),
+            Err(Error::OperationTimedOut) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
     /// Read a single line status change event from the chip. If no events are
     /// pending, this function will block.
     pub fn read_info_event(&self) -> Result<InfoEvent> {
@@ -164,7 +364,7 @@ impl Chip {
         };
 
         if ret == -1 {
-            Err(Error::OperationFailed(
+            Err(Error::device_op_failed(
                 "Gpio Chip find-line",
                 IoError::last(),
             ))
@@ -173,6 +373,74 @@ impl Chip {
         }
     }
 
+    /// Map a GPIO line's name to the offsets of all the lines within the chip
+    /// sharing it.
+    ///
+    /// Unlike `find_line()`, which stops at the first match, this scans all
+    /// the lines of the chip. An empty vector is returned if no line matches.
+    pub fn find_all_lines(&self, name: &str) -> Result<Vec<u32>> {
+        let mut offsets = Vec::new();
+
+        for offset in 0..self.get_num_lines() {
+            let info = self.line_info(offset)?;
+
+            if let Ok(line) = info.get_name() {
+                if line == name {
+                    offsets.push(offset);
+                }
+            }
+        }
+
+        Ok(offsets)
+    }
+
+    /// Get the offsets of all the lines of the chip held by the given consumer.
+    ///
+    /// An empty vector is returned if no line matches.
+    pub fn lines_by_consumer(&self, consumer: &str) -> Result<Vec<u32>> {
+        let mut offsets = Vec::new();
+
+        for offset in 0..self.get_num_lines() {
+            let info = self.line_info(offset)?;
+
+            if let Ok(line_consumer) = info.get_consumer() {
+                if line_consumer == consumer {
+                    offsets.push(offset);
+                }
+            }
+        }
+
+        Ok(offsets)
+    }
+
+    /// Get the offsets of all the lines of the chip with edge detection
+    /// enabled, along with the detected edges.
+    pub fn lines_with_edge_detection(&self) -> Result<Vec<(u32, Edge)>> {
+        let mut lines = Vec::new();
+
+        for offset in 0..self.get_num_lines() {
+            let edge = self.line_info(offset)?.get_edge_detection()?;
+
+            if edge != Edge::None {
+                lines.push((offset, edge));
+            }
+        }
+
+        Ok(lines)
+    }
+
+    /// Get a snapshot of information about the line with given name.
+    pub fn get_line_info_by_name(&self, name: &str) -> Result<LineInfo> {
+        let offset = match self.find_line(name) {
+            Err(Error::OperationFailed(_, err)) if err.errno() == ENOENT => {
+                return Err(Error::NameNotFound("GPIO line"))
+            }
+            ret => ret?,
+        };
+
+        self.line_info(offset)
+    }
+
     /// Request a set of lines for exclusive usage.
     pub fn request_lines(
         &self,
@@ -181,4 +449,167 @@ impl Chip {
     ) -> Result<LineRequest> {
         LineRequest::new(&self.ichip, rconfig, lconfig)
     }
+
+    /// Request a set of lines for exclusive usage, retrying while any of them
+    /// is busy.
+    ///
+    /// The request is attempted up to `attempts` times, at least once,
+    /// sleeping for `backoff` after each attempt failing with `EBUSY`. Returns
+    /// the last error if all the attempts fail.
+    pub fn request_lines_retry(
+        &self,
+        rconfig: &RequestConfig,
+        lconfig: &LineConfig,
+        attempts: u32,
+        backoff: Duration,
+    ) -> Result<LineRequest> {
+        let mut attempt = 1;
+
+        loop {
+            match self.request_lines(rconfig, lconfig) {
+                Err(Error::OperationFailed(_, err))
+                    if err.errno() == EBUSY && attempt < attempts =>
+                {
+                    attempt += 1;
+                    sleep(backoff);
+                }
+                ret => return ret,
+            }
+        }
+    }
+
+    /// Request a set of lines for exclusive usage, validating the offsets
+    /// first.
+    ///
+    /// Returns `Error::InvalidValue` for offsets out of the range of the chip
+    /// and `Error::DuplicateOffset` for offsets requested more than once,
+    /// instead of the kernel's errno.
+    pub fn request_lines_checked(
+        &self,
+        rconfig: &RequestConfig,
+        lconfig: &LineConfig,
+    ) -> Result<LineRequest> {
+        let mut offsets = HashSet::new();
+
+        for offset in rconfig.get_offsets() {
+            if offset >= self.get_num_lines() {
+                return Err(Error::InvalidValue("offset", offset));
+            }
+
+            if !offsets.insert(offset) {
+                return Err(Error::DuplicateOffset(offset));
+            }
+        }
+
+        self.request_lines(rconfig, lconfig)
+    }
+
+    /// Request a set of lines as push-pull outputs, driven to the given values.
+    ///
+    /// Each `(offset, value)` pair of `spec` is requested with its own
+    /// initial value. The direction and the values are passed to the kernel in
+    /// the request itself, the lines are never driven to a default value
+    /// before being set. Fails, without requesting any line, if a value isn't
+    /// 0 or 1.
+    pub fn request_output_safe(&self, consumer: &str, spec: &[(u32, i32)]) -> Result<LineRequest> {
+        let mut lconfig = LineConfig::new()?;
+        lconfig.configure_outputs(spec, Drive::PushPull)?;
+
+        let rconfig = RequestConfig::new()?;
+        let offsets: Vec<u32> = spec.iter().map(|(offset, _)| *offset).collect();
+
+        rconfig.set_consumer(consumer);
+        rconfig.set_offsets(&offsets);
+
+        self.request_lines(&rconfig, &lconfig)
+    }
+
+    /// Read the values of a set of lines in one go.
+    ///
+    /// The lines are requested as inputs, read and released before returning.
+    /// The values are in the order of the offsets.
+    pub fn read_values_once(&self, consumer: &str, offsets: &[u32]) -> Result<Vec<i32>> {
+        let rconfig = RequestConfig::new()?;
+        let mut lconfig = LineConfig::new()?;
+
+        rconfig.set_consumer(consumer);
+        rconfig.set_offsets(offsets);
+        lconfig.set_input();
+
+        let request = self.request_lines(&rconfig, &lconfig)?;
+        let mut values = vec![0; request.len()];
+
+        request.get_values(&mut values)?;
+        request.release();
+        Ok(values)
+    }
+
+    /// Drive a set of lines to the given values in one go.
+    ///
+    /// The lines are requested as outputs, see `request_output_safe()`, and
+    /// released before returning, after being held for `hold` if given. Once
+    /// released, the state of the lines is undefined.
+    pub fn write_values_once(
+        &self,
+        consumer: &str,
+        spec: &[(u32, i32)],
+        hold: Option<Duration>,
+    ) -> Result<()> {
+        let request = self.request_output_safe(consumer, spec)?;
+
+        if let Some(hold) = hold {
+            sleep(hold);
+        }
+
+        request.release();
+        Ok(())
+    }
+
+    /// Create a builder for a line request on the chip, combining the request
+    /// config and the line config in a single fluent interface.
+    pub fn request_builder(&self) -> Result<RequestBuilder<'_>> {
+        RequestBuilder::new(self)
+    }
+}
+
+/// Chips are compared by the path used to find them, not by the underlying
+/// file descriptor. Two `Chip`s opened using the same path compare equal.
+impl PartialEq for Chip {
+    fn eq(&self, other: &Self) -> bool {
+        self.path_bytes() == other.path_bytes()
+    }
+}
+
+impl Eq for Chip {}
+
+impl PartialOrd for Chip {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Chip {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.path_bytes().cmp(other.path_bytes())
+    }
+}
+
+impl Hash for Chip {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.path_bytes().hash(state);
+    }
+}
+
+impl fmt::Display for Chip {
+    /// Prints the name, label and number of lines of the chip, from the cached
+    /// chip information, e.g. "gpiochip0 [label] (8 lines)".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} [{}] ({} lines)",
+            self.get_name().unwrap_or("?"),
+            self.get_label().unwrap_or("?"),
+            self.get_num_lines()
+        )
+    }
 }
@@ -21,7 +21,7 @@ impl ChipInfo {
     pub(crate) fn new(chip: Arc<ChipInternal>) -> Result<Self> {
         let info = unsafe { bindings::gpiod_chip_get_info(chip.chip()) };
         if info.is_null() {
-            return Err(Error::OperationFailed(
+            return Err(Error::device_op_failed(
                 "Gpio Chip get info",
                 IoError::last(),
             ));
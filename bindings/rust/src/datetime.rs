@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+//! Private helpers converting event timestamps to chrono date and time.
+
+use libc::{clock_gettime, clockid_t, timespec, CLOCK_MONOTONIC, CLOCK_REALTIME};
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+/// Convert a timestamp read from the realtime clock.
+pub(crate) fn from_realtime(timestamp: Duration) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(timestamp.as_secs() as i64, timestamp.subsec_nanos())
+        .single()
+}
+
+/// Convert a timestamp read from the monotonic clock, by applying the current
+/// offset between the realtime and monotonic clocks.
+pub(crate) fn from_monotonic(timestamp: Duration) -> Option<DateTime<Utc>> {
+    let realtime = now(CLOCK_REALTIME)?;
+    let monotonic = now(CLOCK_MONOTONIC)?;
+
+    from_realtime(realtime.checked_sub(monotonic)?.checked_add(timestamp)?)
+}
+
+/// Read the current time of the clock.
+fn now(clock: clockid_t) -> Option<Duration> {
+    let mut ts = timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+
+    if unsafe { clock_gettime(clock, &mut ts) } != 0 {
+        return None;
+    }
+
+    Some(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+}
@@ -8,7 +8,12 @@ use std::time::Duration;
 
 use vmm_sys_util::errno::Error as IoError;
 
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
 use super::{bindings, EdgeEventBufferInternal, Error, LineEdgeEvent, Result};
+#[cfg(feature = "chrono")]
+use super::{datetime, EventClock};
 
 /// Line edge events handling
 ///
@@ -69,7 +74,25 @@ impl EdgeEvent {
 
     /// Get the timestamp of the event.
     pub fn get_timestamp(&self) -> Duration {
-        Duration::from_nanos(unsafe { bindings::gpiod_edge_event_get_timestamp_ns(self.event) })
+        Duration::from_nanos(self.get_timestamp_ns())
+    }
+
+    /// Get the timestamp of the event in nanoseconds, as read from the clock.
+    pub fn get_timestamp_ns(&self) -> u64 {
+        unsafe { bindings::gpiod_edge_event_get_timestamp_ns(self.event) }
+    }
+
+    /// Get the timestamp of the event as a date and time.
+    ///
+    /// The event itself doesn't record which clock the timestamp was read
+    /// from, `clock` must be the event clock configured for the line. Returns
+    /// `None` unless the line used the realtime clock.
+    #[cfg(feature = "chrono")]
+    pub fn timestamp_datetime(&self, clock: EventClock) -> Option<DateTime<Utc>> {
+        match clock {
+            EventClock::Realtime => datetime::from_realtime(self.get_timestamp()),
+            EventClock::Monotonic => None,
+        }
     }
 
     /// Get the offset of the line on which the event was triggered.
@@ -92,8 +115,78 @@ impl EdgeEvent {
     pub fn get_line_seqno(&self) -> u64 {
         unsafe { bindings::gpiod_edge_event_get_line_seqno(self.event) }
     }
+
+    /// Copy the edge event into an owned event.
+    pub fn to_owned(&self) -> Result<OwnedEdgeEvent> {
+        Ok(OwnedEdgeEvent {
+            event_type: self.get_event_type()?,
+            timestamp_ns: self.get_timestamp_ns(),
+            offset: self.get_line_offset(),
+            global_seqno: self.get_global_seqno(),
+            line_seqno: self.get_line_seqno(),
+        })
+    }
+}
+
+/// Owned line edge event
+///
+/// A copy of an edge event, which doesn't refer to any libgpiod object and
+/// can be freely cloned and sent across threads.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnedEdgeEvent {
+    event_type: LineEdgeEvent,
+    timestamp_ns: u64,
+    offset: u32,
+    global_seqno: u64,
+    line_seqno: u64,
+}
+
+impl OwnedEdgeEvent {
+    /// Get the event type.
+    pub fn get_event_type(&self) -> LineEdgeEvent {
+        self.event_type
+    }
+
+    /// Get the timestamp of the event.
+    pub fn get_timestamp(&self) -> Duration {
+        Duration::from_nanos(self.timestamp_ns)
+    }
+
+    /// Get the timestamp of the event in nanoseconds, as read from the clock.
+    pub fn get_timestamp_ns(&self) -> u64 {
+        self.timestamp_ns
+    }
+
+    /// Get the offset of the line on which the event was triggered.
+    pub fn get_line_offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Get the global sequence number of the event.
+    pub fn get_global_seqno(&self) -> u64 {
+        self.global_seqno
+    }
+
+    /// Get the event sequence number specific to concerned line.
+    pub fn get_line_seqno(&self) -> u64 {
+        self.line_seqno
+    }
 }
 
+impl PartialEq for EdgeEvent {
+    /// Compare the contents of the events, regardless of whether they are
+    /// stored in a buffer or copies.
+    fn eq(&self, other: &Self) -> bool {
+        self.get_event_type() == other.get_event_type()
+            && self.get_line_offset() == other.get_line_offset()
+            && self.get_timestamp() == other.get_timestamp()
+            && self.get_global_seqno() == other.get_global_seqno()
+            && self.get_line_seqno() == other.get_line_seqno()
+    }
+}
+
+impl Eq for EdgeEvent {}
+
 impl Drop for EdgeEvent {
     /// Free the edge event.
     fn drop(&mut self) {
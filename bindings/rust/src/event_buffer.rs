@@ -10,7 +10,11 @@ use vmm_sys_util::errno::Error as IoError;
 
 use super::{bindings, EdgeEvent, Error, Result};
 
+/// Maximum number of events an edge event buffer can hold.
+pub const MAX_EDGE_EVENT_BUFFER_CAPACITY: u32 = 1024;
+
 /// Line edge events buffer
+#[derive(Debug)]
 pub(crate) struct EdgeEventBufferInternal {
     buffer: *mut bindings::gpiod_edge_event_buffer,
 }
@@ -46,6 +50,7 @@ impl Drop for EdgeEventBufferInternal {
 }
 
 /// Line edge events buffer
+#[derive(Debug)]
 pub struct EdgeEventBuffer {
     ibuffer: Arc<EdgeEventBufferInternal>,
 }
@@ -61,11 +66,33 @@ impl EdgeEventBuffer {
         })
     }
 
+    /// Create a new edge event buffer, without clamping the capacity.
+    ///
+    /// If capacity equals 0, it will be set to a default value of 64. If
+    /// capacity is larger than `MAX_EDGE_EVENT_BUFFER_CAPACITY`, an error is
+    /// returned.
+    pub fn try_new(capacity: u32) -> Result<Self> {
+        if capacity > MAX_EDGE_EVENT_BUFFER_CAPACITY {
+            return Err(Error::InvalidValue("capacity", capacity));
+        }
+
+        Self::new(capacity)
+    }
+
     /// Private helper, Returns gpiod_edge_event_buffer
     pub(crate) fn buffer(&self) -> *mut bindings::gpiod_edge_event_buffer {
         self.ibuffer.buffer()
     }
 
+    /// Replace the buffer with a new one of the given capacity.
+    ///
+    /// The events currently stored in the buffer are discarded. The capacity
+    /// is handled the same way as by `new()`.
+    pub fn resize(&mut self, new_capacity: u32) -> Result<()> {
+        *self = Self::new(new_capacity)?;
+        Ok(())
+    }
+
     /// Get the capacity of the event buffer.
     pub fn get_capacity(&self) -> u32 {
         unsafe { bindings::gpiod_edge_event_buffer_get_capacity(self.buffer()) as u32 }
@@ -85,4 +112,12 @@ impl EdgeEventBuffer {
     pub fn get_num_events(&self) -> u32 {
         unsafe { bindings::gpiod_edge_event_buffer_get_num_events(self.buffer()) as u32 }
     }
+
+    /// Iterate over all the events currently stored in the buffer.
+    ///
+    /// libgpiod doesn't provide a way to clear the buffer, its contents are
+    /// only replaced by the next call to `LineRequest::read_edge_event()`.
+    pub fn drain(&self) -> impl Iterator<Item = Result<EdgeEvent>> + '_ {
+        (0..self.get_num_events()).map(move |index| self.get_event(index as u64))
+    }
 }
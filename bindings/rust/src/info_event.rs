@@ -4,12 +4,18 @@
 //     Viresh Kumar <viresh.kumar@linaro.org>
 
 use std::convert::TryFrom;
+use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 
 use vmm_sys_util::errno::Error as IoError;
 
-use super::{bindings, ChipInternal, Error, Event, LineInfo, Result};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "chrono")]
+use super::datetime;
+use super::{bindings, ChipInternal, Error, Event, LineInfo, LineInfoSnapshot, Result};
 
 /// Line status watch events
 ///
@@ -30,7 +36,7 @@ impl InfoEvent {
     pub(crate) fn new(ichip: &Arc<ChipInternal>) -> Result<Self> {
         let event = unsafe { bindings::gpiod_chip_read_info_event(ichip.chip()) };
         if event.is_null() {
-            return Err(Error::OperationFailed(
+            return Err(Error::device_op_failed(
                 "Gpio InfoEvent event-read",
                 IoError::last(),
             ));
@@ -51,13 +57,80 @@ impl InfoEvent {
 
     /// Get the timestamp of the event, read from the monotonic clock.
     pub fn get_timestamp(&self) -> Duration {
-        Duration::from_nanos(unsafe { bindings::gpiod_info_event_get_timestamp_ns(self.event) })
+        Duration::from_nanos(self.get_timestamp_ns())
+    }
+
+    /// Get the timestamp of the event in nanoseconds, read from the monotonic
+    /// clock.
+    pub fn get_timestamp_ns(&self) -> u64 {
+        unsafe { bindings::gpiod_info_event_get_timestamp_ns(self.event) }
+    }
+
+    /// Get the timestamp of the event as a date and time.
+    ///
+    /// Info event timestamps are read from the monotonic clock, they are
+    /// converted using the current offset between the realtime and monotonic
+    /// clocks. The result is approximate, it is off by any adjustment made
+    /// to the realtime clock since the event occurred.
+    #[cfg(feature = "chrono")]
+    pub fn timestamp_datetime(&self) -> Option<DateTime<Utc>> {
+        datetime::from_monotonic(self.get_timestamp())
     }
 
     /// Get the line-info object associated with the event.
     pub fn line_info(&self) -> Result<LineInfo> {
         LineInfo::try_from(self)
     }
+
+    /// Copy the event, along with its line info, into an owned object.
+    ///
+    /// Unlike `InfoEvent`, the returned object can be sent to other threads.
+    pub fn to_owned(&self) -> Result<OwnedInfoEvent> {
+        Ok(OwnedInfoEvent {
+            event_type: self.get_event_type()?,
+            timestamp: self.get_timestamp(),
+            info: self.line_info()?.snapshot()?,
+        })
+    }
+}
+
+impl fmt::Debug for InfoEvent {
+    /// Print the event type, timestamp and the offset of the line of the event.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InfoEvent")
+            .field("event_type", &self.get_event_type())
+            .field("timestamp", &self.get_timestamp())
+            .field("offset", &self.line_info().map(|info| info.get_offset()))
+            .finish()
+    }
+}
+
+/// Owned line status watch event
+///
+/// A copy of an info event, holding the event type, its timestamp and a
+/// snapshot of line's state at the time of the event.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnedInfoEvent {
+    event_type: Event,
+    timestamp: Duration,
+    info: LineInfoSnapshot,
+}
+
+impl OwnedInfoEvent {
+    /// Get the event type of the status change event.
+    pub fn get_event_type(&self) -> Event {
+        self.event_type
+    }
+
+    /// Get the timestamp of the event, read from the monotonic clock.
+    pub fn get_timestamp(&self) -> Duration {
+        self.timestamp
+    }
+
+    /// Get the snapshot of line's state associated with the event.
+    pub fn line_info(&self) -> &LineInfoSnapshot {
+        &self.info
+    }
 }
 
 impl Drop for InfoEvent {
@@ -15,13 +15,26 @@
 
 mod chip;
 mod chip_info;
+#[cfg(feature = "chrono")]
+mod datetime;
 mod edge_event;
 mod event_buffer;
 mod info_event;
 mod line_config;
 mod line_info;
 mod line_request;
+mod monitor;
+mod poll;
+mod request_builder;
 mod request_config;
+mod wait_canceller;
+
+#[cfg(feature = "async-io")]
+pub mod async_io;
+#[cfg(feature = "calloop")]
+pub mod calloop;
+#[cfg(feature = "gpiosim")]
+pub mod sim;
 
 use libgpiod_sys as bindings;
 
@@ -32,10 +45,19 @@ pub use crate::info_event::*;
 pub use crate::line_config::*;
 pub use crate::line_info::*;
 pub use crate::line_request::*;
+pub use crate::monitor::*;
+pub use crate::poll::*;
+pub use crate::request_builder::*;
 pub use crate::request_config::*;
+pub use crate::wait_canceller::*;
 
+use libc::{uname, utsname, EACCES, EIO, ENODEV};
+use std::convert::TryFrom;
+use std::ffi::CStr;
+use std::ops::Not;
 use std::os::raw::c_char;
-use std::{slice, str};
+use std::time::Duration;
+use std::{fmt, fs, mem, slice, str};
 
 use thiserror::Error as ThisError;
 use vmm_sys_util::errno::Error as IoError;
@@ -52,14 +74,72 @@ pub enum Error {
     InvalidString(str::Utf8Error),
     #[error("Invalid {0} value: {1}")]
     InvalidValue(&'static str, u32),
+    #[error("Invalid {0} in argument")]
+    InvalidArgument(&'static str),
+    #[error("File is not a GPIO chip character device")]
+    NotAGpioChip,
+    #[error("Duplicate offset: {0}")]
+    DuplicateOffset(u32),
+    #[error("Offset {0} not part of the line request")]
+    OffsetNotRequested(u32),
     #[error("Operation {0} Failed: {1}")]
-    OperationFailed(&'static str, IoError),
+    OperationFailed(&'static str, #[source] IoError),
     #[error("Operation Timed-out")]
     OperationTimedOut,
+    #[error("Operation Cancelled")]
+    Cancelled,
+    #[error("Operation {0} Failed: GPIO chip removed")]
+    ChipRemoved(&'static str),
+    #[error("Operation {0} Failed: {1:?} not supported by the running kernel")]
+    FeatureNotSupported(&'static str, Feature),
+    #[error("Operation {0} Failed: Permission denied, check the access rights to the GPIO chip")]
+    PermissionDenied(&'static str),
+}
+
+impl Error {
+    /// Private helper, maps the failure of an operation to an error, reporting
+    /// missing permissions separately.
+    pub(crate) fn op_failed(op: &'static str, err: IoError) -> Self {
+        if err.errno() == EACCES {
+            Error::PermissionDenied(op)
+        } else {
+            Error::OperationFailed(op, err)
+        }
+    }
+
+    /// Private helper, maps the failure of an operation on an open chip to an
+    /// error, reporting removal of the underlying device separately.
+    pub(crate) fn device_op_failed(op: &'static str, err: IoError) -> Self {
+        if err.errno() == ENODEV {
+            Error::ChipRemoved(op)
+        } else {
+            Error::op_failed(op, err)
+        }
+    }
+}
+
+/// Line offset
+///
+/// The offset of a line on its chip. Methods accepting an `impl Into<Offset>`
+/// take either a bare `u32` or an `Offset`, the latter keeps offsets from
+/// being mixed up with values or indices.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Offset(pub u32);
+
+impl From<u32> for Offset {
+    fn from(offset: u32) -> Self {
+        Offset(offset)
+    }
+}
+
+impl From<Offset> for u32 {
+    fn from(offset: Offset) -> Self {
+        offset.0
+    }
 }
 
 /// Direction settings.
-#[derive(Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Direction {
     /// Request the line(s), but don't change direction.
     AsIs,
@@ -86,10 +166,25 @@ impl Direction {
             Direction::Output => bindings::GPIOD_LINE_DIRECTION_OUTPUT,
         }
     }
+
+    /// Returns true if the direction is input.
+    pub fn is_input(&self) -> bool {
+        *self == Direction::Input
+    }
+
+    /// Returns true if the direction is output.
+    pub fn is_output(&self) -> bool {
+        *self == Direction::Output
+    }
+
+    /// Returns true if the direction is left as is.
+    pub fn is_as_is(&self) -> bool {
+        *self == Direction::AsIs
+    }
 }
 
 /// Internal bias settings.
-#[derive(Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Bias {
     /// Don't change the bias setting when applying line config.
     AsIs,
@@ -127,7 +222,7 @@ impl Bias {
 }
 
 /// Drive settings.
-#[derive(Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Drive {
     /// Drive setting is push-pull.
     PushPull,
@@ -157,7 +252,7 @@ impl Drive {
 }
 
 /// Edge detection settings.
-#[derive(Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Edge {
     /// Line edge detection is disabled.
     None,
@@ -180,6 +275,27 @@ impl Edge {
         }
     }
 
+    /// Get the edge detection setting detecting the rising and/or falling
+    /// edges.
+    pub fn from_flags(rising: bool, falling: bool) -> Self {
+        match (rising, falling) {
+            (false, false) => Edge::None,
+            (true, false) => Edge::Rising,
+            (false, true) => Edge::Falling,
+            (true, true) => Edge::Both,
+        }
+    }
+
+    /// Returns whether rising and falling edges, in that order, are detected.
+    pub fn flags(&self) -> (bool, bool) {
+        match self {
+            Edge::None => (false, false),
+            Edge::Rising => (true, false),
+            Edge::Falling => (false, true),
+            Edge::Both => (true, true),
+        }
+    }
+
     fn gpiod_edge(&self) -> u32 {
         match self {
             Edge::None => bindings::GPIOD_LINE_EDGE_NONE,
@@ -191,6 +307,7 @@ impl Edge {
 }
 
 /// Line config settings.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Config {
     /// Line direction.
     Direction,
@@ -227,7 +344,7 @@ impl Config {
 }
 
 /// Event clock settings.
-#[derive(Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub enum EventClock {
     /// Line uses the monotonic clock for edge event timestamps.
     Monotonic,
@@ -252,8 +369,80 @@ impl EventClock {
     }
 }
 
+/// Line values.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LineValue {
+    /// Line is inactive.
+    InActive,
+    /// Line is active.
+    Active,
+}
+
+impl LineValue {
+    fn new(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(LineValue::InActive),
+            1 => Ok(LineValue::Active),
+            _ => Err(Error::InvalidValue("line", value)),
+        }
+    }
+}
+
+impl TryFrom<u32> for LineValue {
+    type Error = Error;
+
+    /// Convert a raw line value, which must be 0 or 1.
+    fn try_from(value: u32) -> Result<Self> {
+        LineValue::new(value)
+    }
+}
+
+impl From<bool> for LineValue {
+    fn from(value: bool) -> Self {
+        if value {
+            LineValue::Active
+        } else {
+            LineValue::InActive
+        }
+    }
+}
+
+impl From<LineValue> for bool {
+    fn from(value: LineValue) -> Self {
+        value == LineValue::Active
+    }
+}
+
+impl From<LineValue> for i32 {
+    fn from(value: LineValue) -> Self {
+        bool::from(value) as i32
+    }
+}
+
+impl PartialEq<bool> for LineValue {
+    fn eq(&self, other: &bool) -> bool {
+        bool::from(*self) == *other
+    }
+}
+
+impl Not for LineValue {
+    type Output = Self;
+
+    /// Toggle the value.
+    fn not(self) -> Self {
+        LineValue::from(!bool::from(self))
+    }
+}
+
+impl fmt::Display for LineValue {
+    /// Prints the value as 0 or 1.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", i32::from(*self))
+    }
+}
+
 /// Line status change event types.
-#[derive(Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Event {
     /// Line has been requested.
     LineRequested,
@@ -293,8 +482,94 @@ impl LineEdgeEvent {
     }
 }
 
+impl From<LineEdgeEvent> for Edge {
+    /// Get the edge detection setting matching the edge event type.
+    fn from(event: LineEdgeEvent) -> Self {
+        match event {
+            LineEdgeEvent::Rising => Edge::Rising,
+            LineEdgeEvent::Falling => Edge::Falling,
+        }
+    }
+}
+
+impl TryFrom<Edge> for LineEdgeEvent {
+    type Error = Error;
+
+    /// Get the edge event type matching the edge detection setting, fails for
+    /// `Edge::None` and `Edge::Both`.
+    fn try_from(edge: Edge) -> Result<Self> {
+        match edge {
+            Edge::Rising => Ok(LineEdgeEvent::Rising),
+            Edge::Falling => Ok(LineEdgeEvent::Falling),
+            _ => Err(Error::InvalidValue("edge event", edge.gpiod_edge())),
+        }
+    }
+}
+
+/// Kernel features, not available on all kernels supported by the library.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Feature {
+    /// Debouncing of input lines by the kernel.
+    Debounce,
+    /// Edge event timestamps read from the realtime clock.
+    RealtimeEventClock,
+}
+
+impl Feature {
+    /// Private helper, Returns the first kernel version (major, minor)
+    /// supporting the feature.
+    fn min_kernel_version(&self) -> (u32, u32) {
+        match self {
+            Feature::Debounce => (5, 10),
+            Feature::RealtimeEventClock => (5, 11),
+        }
+    }
+}
+
+/// Private helper, Returns the (major, minor) version of the running kernel.
+fn kernel_version() -> Option<(u32, u32)> {
+    let mut name: utsname = unsafe { mem::zeroed() };
+
+    if unsafe { uname(&mut name) } != 0 {
+        return None;
+    }
+
+    // SAFETY: uname() guarantees the release string is null-terminated.
+    let release = unsafe { CStr::from_ptr(name.release.as_ptr()) }
+        .to_str()
+        .ok()?;
+    let mut numbers = release
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|number| number.parse::<u32>());
+
+    match (numbers.next()?, numbers.next()?) {
+        (Ok(major), Ok(minor)) => Some((major, minor)),
+        _ => None,
+    }
+}
+
+/// Private helper, Converts a timeout to nanoseconds as expected by libgpiod.
+///
+/// Saturates to `i64::MAX` (about 292 years) instead of wrapping around to a
+/// short or negative timeout.
+pub(crate) fn duration_to_timeout_ns(timeout: Duration) -> i64 {
+    i64::try_from(timeout.as_nanos()).unwrap_or(i64::MAX)
+}
+
 /// Various libgpiod-related functions.
 
+/// Check if the running kernel supports the feature.
+///
+/// This is based on the version of the running kernel, it allows picking a
+/// fallback before a line request fails with EINVAL. Returns false if the
+/// kernel version can't be determined.
+pub fn feature_supported(feature: Feature) -> bool {
+    match kernel_version() {
+        Some(version) => version >= feature.min_kernel_version(),
+        None => false,
+    }
+}
+
 /// Check if the file pointed to by path is a GPIO chip character device.
 ///
 /// Returns true if the file exists and is a GPIO chip character device or a
@@ -306,6 +581,48 @@ pub fn gpiod_is_gpiochip_device(path: &str) -> bool {
     unsafe { bindings::gpiod_is_gpiochip_device(path.as_ptr() as *const c_char) }
 }
 
+/// Count the GPIO chip character devices in `/dev`.
+///
+/// The chips aren't opened, which makes this cheaper than opening each of them
+/// when only the number of chips is needed.
+pub fn count_gpiochips() -> Result<usize> {
+    let entries = fs::read_dir("/dev").map_err(|err| {
+        Error::OperationFailed(
+            "Gpio count-gpiochips",
+            IoError::new(err.raw_os_error().unwrap_or(EIO)),
+        )
+    })?;
+
+    Ok(entries
+        .flatten()
+        .filter(
+            |entry| matches!(entry.path().to_str(), Some(path) if gpiod_is_gpiochip_device(path)),
+        )
+        .count())
+}
+
+/// Parse a line argument of the form `offset` or `offset=value`, as taken by
+/// the gpioget and gpioset tools.
+///
+/// Returns the offset and the value, if any. Surrounding whitespace isn't
+/// accepted.
+pub fn parse_offset_value(arg: &str) -> Result<(u32, Option<i32>)> {
+    let (offset, value) = match arg.split_once('=') {
+        Some((offset, value)) => (offset, Some(value)),
+        None => (arg, None),
+    };
+
+    let offset = offset
+        .parse::<u32>()
+        .map_err(|_| Error::InvalidArgument("offset"))?;
+    let value = value
+        .map(|value| value.parse::<i32>())
+        .transpose()
+        .map_err(|_| Error::InvalidArgument("value"))?;
+
+    Ok((offset, value))
+}
+
 /// Get the API version of the library as a human-readable string.
 pub fn gpiod_version_string() -> Result<&'static str> {
     // SAFETY: The string returned by libgpiod is guaranteed to live forever.
@@ -321,3 +638,71 @@ pub fn gpiod_version_string() -> Result<&'static str> {
     })
     .map_err(Error::InvalidString)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeout_ns() {
+        assert_eq!(duration_to_timeout_ns(Duration::from_millis(1)), 1_000_000);
+        assert_eq!(
+            duration_to_timeout_ns(Duration::from_nanos(i64::MAX as u64)),
+            i64::MAX
+        );
+        assert_eq!(
+            duration_to_timeout_ns(Duration::from_nanos(i64::MAX as u64) + Duration::from_nanos(1)),
+            i64::MAX
+        );
+        assert_eq!(duration_to_timeout_ns(Duration::MAX), i64::MAX);
+    }
+
+    #[test]
+    fn error_source() {
+        let err = Error::OperationFailed("Gpio Chip open", IoError::new(ENODEV));
+        let source = std::error::Error::source(&err).unwrap();
+
+        assert_eq!(source.to_string(), IoError::new(ENODEV).to_string());
+        assert!(std::error::Error::source(&Error::OperationTimedOut).is_none());
+    }
+
+    #[test]
+    fn permission_denied() {
+        assert_eq!(
+            Error::op_failed("Gpio Chip open", IoError::new(EACCES)),
+            Error::PermissionDenied("Gpio Chip open")
+        );
+        assert_eq!(
+            Error::device_op_failed("Gpio LineRequest request-lines", IoError::new(EACCES)),
+            Error::PermissionDenied("Gpio LineRequest request-lines")
+        );
+        assert_eq!(
+            Error::op_failed("Gpio Chip open", IoError::new(ENODEV)),
+            Error::OperationFailed("Gpio Chip open", IoError::new(ENODEV))
+        );
+    }
+
+    #[test]
+    fn offset_value() {
+        assert_eq!(parse_offset_value("4").unwrap(), (4, None));
+        assert_eq!(parse_offset_value("4=1").unwrap(), (4, Some(1)));
+        assert_eq!(parse_offset_value("12=0").unwrap(), (12, Some(0)));
+    }
+
+    #[test]
+    fn malformed_offset_value() {
+        for arg in ["", "x", "-1", "4 ", "=1", "x=1"] {
+            assert_eq!(
+                parse_offset_value(arg).unwrap_err(),
+                Error::InvalidArgument("offset")
+            );
+        }
+
+        for arg in ["4=", "4=x", "4=1=0"] {
+            assert_eq!(
+                parse_offset_value(arg).unwrap_err(),
+                Error::InvalidArgument("value")
+            );
+        }
+    }
+}
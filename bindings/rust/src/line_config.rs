@@ -4,7 +4,7 @@
 //     Viresh Kumar <viresh.kumar@linaro.org>
 
 use libc::EINVAL;
-use std::os::raw::c_ulong;
+use std::os::raw::{c_int, c_ulong};
 use std::time::Duration;
 
 use vmm_sys_util::errno::Error as IoError;
@@ -46,6 +46,7 @@ use super::{bindings, Bias, Config, Direction, Drive, Edge, Error, EventClock, R
 /// In cases where all requested lines are using the one configuration, the
 /// line overrides can be entirely ignored when preparing the configuration.
 
+#[derive(Debug)]
 pub struct LineConfig {
     config: *mut bindings::gpiod_line_config,
 }
@@ -70,12 +71,63 @@ impl LineConfig {
         self.config
     }
 
+    /// Get the underlying libgpiod line config object.
+    ///
+    /// # Safety
+    ///
+    /// This is an escape hatch for calling libgpiod functions not wrapped by
+    /// this crate. The pointer is owned by the `LineConfig`, it must not be
+    /// freed by the caller and must not be used after the `LineConfig` is
+    /// dropped.
+    pub unsafe fn as_raw_ptr(&self) -> *mut bindings::gpiod_line_config {
+        self.config
+    }
+
     /// Resets the entire configuration stored in the object. This is useful if
     /// the user wants to reuse the object without reallocating it.
     pub fn reset(&mut self) {
         unsafe { bindings::gpiod_line_config_reset(self.config) }
     }
 
+    /// Configure the lines as inputs.
+    ///
+    /// Sets the default direction to input and disables edge event detection
+    /// by default. Overrides are left untouched.
+    pub fn set_input(&mut self) {
+        self.set_direction_default(Direction::Input);
+        self.set_edge_detection_default(Edge::None);
+    }
+
+    /// Configure the lines as outputs driven to the given value.
+    ///
+    /// Sets the default direction to output and the default output value.
+    /// Overrides are left untouched.
+    pub fn set_output(&mut self, value: u32) {
+        self.set_direction_default(Direction::Output);
+        self.set_output_value_default(value);
+    }
+
+    /// Configure the lines as outputs with the given drive and per-line
+    /// output values.
+    ///
+    /// Sets the default direction to output and the default drive, and
+    /// overrides the output value of each `(offset, value)` pair of `spec`.
+    /// Fails, without changing the config, if a value isn't 0 or 1.
+    pub fn configure_outputs(&mut self, spec: &[(u32, i32)], drive: Drive) -> Result<()> {
+        if let Some((_, value)) = spec.iter().find(|(_, value)| *value != 0 && *value != 1) {
+            return Err(Error::InvalidValue("output", *value as u32));
+        }
+
+        self.set_direction_default(Direction::Output);
+        self.set_drive_default(drive);
+
+        for (offset, value) in spec {
+            self.set_output_value_override(*value as u32, *offset);
+        }
+
+        Ok(())
+    }
+
     /// Set the default line direction.
     pub fn set_direction_default(&mut self, direction: Direction) {
         unsafe {
@@ -144,6 +196,17 @@ impl LineConfig {
         }
     }
 
+    /// Set the same edge event detection for a set of lines.
+    pub fn set_edge_detection_overrides(&mut self, edge: Edge, offsets: &[u32]) {
+        let edge = edge.gpiod_edge() as i32;
+
+        for offset in offsets {
+            unsafe {
+                bindings::gpiod_line_config_set_edge_detection_override(self.config, edge, *offset)
+            }
+        }
+    }
+
     /// Clear the edge event detection for a single line.
     pub fn clear_edge_detection_override(&mut self, offset: u32) {
         unsafe { bindings::gpiod_line_config_clear_edge_detection_override(self.config, offset) }
@@ -188,6 +251,15 @@ impl LineConfig {
         }
     }
 
+    /// Set the same bias for a set of lines.
+    pub fn set_bias_overrides(&mut self, bias: Bias, offsets: &[u32]) {
+        let bias = bias.gpiod_bias() as i32;
+
+        for offset in offsets {
+            unsafe { bindings::gpiod_line_config_set_bias_override(self.config, bias, *offset) }
+        }
+    }
+
     /// Clear the bias for a single line.
     pub fn clear_bias_override(&mut self, offset: u32) {
         unsafe { bindings::gpiod_line_config_clear_bias_override(self.config, offset) }
@@ -230,6 +302,15 @@ impl LineConfig {
         }
     }
 
+    /// Set the same drive for a set of lines.
+    pub fn set_drive_overrides(&mut self, drive: Drive, offsets: &[u32]) {
+        let drive = drive.gpiod_drive() as i32;
+
+        for offset in offsets {
+            unsafe { bindings::gpiod_line_config_set_drive_override(self.config, drive, *offset) }
+        }
+    }
+
     /// clear the drive for a single line.
     pub fn clear_drive_override(&mut self, offset: u32) {
         unsafe { bindings::gpiod_line_config_clear_drive_override(self.config, offset) }
@@ -370,6 +451,35 @@ impl LineConfig {
         unsafe { bindings::gpiod_line_config_event_clock_is_overridden(self.config, offset) }
     }
 
+    /// Read edge event timestamps from the realtime clock by default.
+    ///
+    /// The realtime clock is supported by Linux 5.11 and later, requests fail
+    /// with `Error::FeatureNotSupported` on older kernels.
+    pub fn use_realtime_clock(&mut self) {
+        self.set_event_clock_default(EventClock::Realtime);
+    }
+
+    /// Read edge event timestamps from the monotonic clock by default.
+    pub fn use_monotonic_clock(&mut self) {
+        self.set_event_clock_default(EventClock::Monotonic);
+    }
+
+    /// Private helper, Returns true if any line uses the event clock, by
+    /// default or through an override.
+    pub(crate) fn uses_event_clock(&self, clock: EventClock) -> bool {
+        if matches!(self.get_event_clock_default(), Ok(default) if default == clock) {
+            return true;
+        }
+
+        match self.get_overrides() {
+            Ok(overrides) => overrides.iter().any(|(offset, config)| {
+                *config == Config::EventClock
+                    && matches!(self.get_event_clock_offset(*offset), Ok(value) if value == clock)
+            }),
+            Err(_) => false,
+        }
+    }
+
     /// Get the default event clock setting.
     pub fn get_event_clock_default(&self) -> Result<EventClock> {
         EventClock::new(
@@ -433,14 +543,7 @@ impl LineConfig {
     pub fn get_output_value_default(&self) -> Result<u32> {
         let value = unsafe { bindings::gpiod_line_config_get_output_value_default(self.config) };
 
-        if value != 0 && value != 1 {
-            Err(Error::OperationFailed(
-                "Gpio LineConfig get-output-value",
-                IoError::last(),
-            ))
-        } else {
-            Ok(value as u32)
-        }
+        Self::output_value_from_ret(value)
     }
 
     /// Get the output value configured for a given line, 0 or 1.
@@ -448,13 +551,14 @@ impl LineConfig {
         let value =
             unsafe { bindings::gpiod_line_config_get_output_value_offset(self.config, offset) };
 
-        if value != 0 && value != 1 {
-            Err(Error::OperationFailed(
-                "Gpio LineConfig get-output-value",
-                IoError::last(),
-            ))
-        } else {
-            Ok(value as u32)
+        Self::output_value_from_ret(value)
+    }
+
+    /// Private helper, Maps the output value returned by libgpiod to a result.
+    fn output_value_from_ret(value: c_int) -> Result<u32> {
+        match value {
+            0 | 1 => Ok(value as u32),
+            _ => Err(Error::InvalidValue("output", value as u32)),
         }
     }
 
@@ -483,6 +587,120 @@ impl LineConfig {
 
         Ok(overrides)
     }
+
+    /// Layer another line config over this one.
+    ///
+    /// The defaults of `other` which differ from the defaults of a new line
+    /// config, along with all of its overrides, are copied onto this config,
+    /// replacing the existing values. Settings left untouched in `other` keep
+    /// their current values.
+    pub fn merge(&mut self, other: &LineConfig) -> Result<()> {
+        let base = LineConfig::new()?;
+
+        if other.get_direction_default()? != base.get_direction_default()? {
+            self.set_direction_default(other.get_direction_default()?);
+        }
+        if other.get_edge_detection_default()? != base.get_edge_detection_default()? {
+            self.set_edge_detection_default(other.get_edge_detection_default()?);
+        }
+        if other.get_bias_default()? != base.get_bias_default()? {
+            self.set_bias_default(other.get_bias_default()?);
+        }
+        if other.get_drive_default()? != base.get_drive_default()? {
+            self.set_drive_default(other.get_drive_default()?);
+        }
+        if other.get_active_low_default() != base.get_active_low_default() {
+            self.set_active_low_default(other.get_active_low_default());
+        }
+        if other.get_debounce_period_default()? != base.get_debounce_period_default()? {
+            self.set_debounce_period_default(other.get_debounce_period_default()?);
+        }
+        if other.get_event_clock_default()? != base.get_event_clock_default()? {
+            self.set_event_clock_default(other.get_event_clock_default()?);
+        }
+        if other.get_output_value_default()? != base.get_output_value_default()? {
+            self.set_output_value_default(other.get_output_value_default()?);
+        }
+
+        for (offset, config) in other.get_overrides()? {
+            match config {
+                Config::Direction => {
+                    self.set_direction_override(other.get_direction_offset(offset)?, offset)
+                }
+                Config::EdgeDetection => self
+                    .set_edge_detection_override(other.get_edge_detection_offset(offset)?, offset),
+                Config::Bias => self.set_bias_override(other.get_bias_offset(offset)?, offset),
+                Config::Drive => self.set_drive_override(other.get_drive_offset(offset)?, offset),
+                Config::ActiveLow => {
+                    self.set_active_low_override(other.get_active_low_offset(offset), offset)
+                }
+                Config::DebouncePeriodUs => self.set_debounce_period_override(
+                    other.get_debounce_period_offset(offset)?,
+                    offset,
+                ),
+                Config::EventClock => {
+                    self.set_event_clock_override(other.get_event_clock_offset(offset)?, offset)
+                }
+                Config::OutputValue => {
+                    self.set_output_value_override(other.get_output_value_offset(offset)?, offset)
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Private helper, Returns the overrides sorted by offset and setting.
+    fn sorted_overrides(&self) -> Result<Vec<(u32, Config)>> {
+        let mut overrides = self.get_overrides()?;
+
+        overrides.sort_by_key(|(offset, config)| (*offset, *config as u32));
+        Ok(overrides)
+    }
+
+    /// Private helper, Returns true if the default settings match.
+    fn defaults_eq(&self, other: &Self) -> bool {
+        self.get_direction_default() == other.get_direction_default()
+            && self.get_edge_detection_default() == other.get_edge_detection_default()
+            && self.get_bias_default() == other.get_bias_default()
+            && self.get_drive_default() == other.get_drive_default()
+            && self.get_active_low_default() == other.get_active_low_default()
+            && self.get_debounce_period_default() == other.get_debounce_period_default()
+            && self.get_event_clock_default() == other.get_event_clock_default()
+            && self.get_output_value_default() == other.get_output_value_default()
+    }
+
+    /// Private helper, Returns true if the effective settings of the line match.
+    fn offset_eq(&self, other: &Self, offset: u32) -> bool {
+        self.get_direction_offset(offset) == other.get_direction_offset(offset)
+            && self.get_edge_detection_offset(offset) == other.get_edge_detection_offset(offset)
+            && self.get_bias_offset(offset) == other.get_bias_offset(offset)
+            && self.get_drive_offset(offset) == other.get_drive_offset(offset)
+            && self.get_active_low_offset(offset) == other.get_active_low_offset(offset)
+            && self.get_debounce_period_offset(offset) == other.get_debounce_period_offset(offset)
+            && self.get_event_clock_offset(offset) == other.get_event_clock_offset(offset)
+            && self.get_output_value_offset(offset) == other.get_output_value_offset(offset)
+    }
+}
+
+impl PartialEq for LineConfig {
+    /// Compare the default settings and the overridden settings of two line
+    /// configs, the order in which the settings were applied doesn't matter.
+    fn eq(&self, other: &Self) -> bool {
+        if !self.defaults_eq(other) {
+            return false;
+        }
+
+        match (self.sorted_overrides(), other.sorted_overrides()) {
+            (Ok(overrides), Ok(other_overrides)) => {
+                overrides == other_overrides
+                    && overrides
+                        .iter()
+                        .all(|(offset, _)| self.offset_eq(other, *offset))
+            }
+            _ => false,
+        }
+    }
 }
 
 impl Drop for LineConfig {
@@ -491,3 +709,18 @@ impl Drop for LineConfig {
         unsafe { bindings::gpiod_line_config_free(self.config) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unexpected_output_value() {
+        assert_eq!(LineConfig::output_value_from_ret(0).unwrap(), 0);
+        assert_eq!(LineConfig::output_value_from_ret(1).unwrap(), 1);
+
+        let err = LineConfig::output_value_from_ret(2).unwrap_err();
+        assert_eq!(err, Error::InvalidValue("output", 2));
+        assert_eq!(err.to_string(), "Invalid output value: 2");
+    }
+}
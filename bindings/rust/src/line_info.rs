@@ -6,12 +6,13 @@
 use std::convert::TryFrom;
 use std::sync::Arc;
 use std::time::Duration;
-use std::{slice, str};
+use std::{fmt, slice, str};
 
 use vmm_sys_util::errno::Error as IoError;
 
 use super::{
-    bindings, Bias, ChipInternal, Direction, Drive, Edge, Error, EventClock, InfoEvent, Result,
+    bindings, Bias, Chip, ChipInternal, Config, Direction, Drive, Edge, Error, EventClock,
+    InfoEvent, Result,
 };
 
 /// Line info
@@ -40,7 +41,7 @@ impl LineInfo {
         };
 
         if info.is_null() {
-            return Err(Error::OperationFailed(
+            return Err(Error::device_op_failed(
                 "Gpio LineInfo line-info",
                 IoError::last(),
             ));
@@ -49,10 +50,22 @@ impl LineInfo {
         Ok(Self {
             info,
             ichip: if watch { Some(ichip) } else { None },
-            free: watch,
+            free: true,
         })
     }
 
+    /// Start watching the line for changes.
+    ///
+    /// The line info is replaced with a fresh snapshot, taken when the watch
+    /// started. Does nothing if the line is already watched.
+    pub fn watch(&mut self, chip: &Chip) -> Result<()> {
+        if self.ichip.is_none() {
+            *self = chip.watch_line_info(self.get_offset())?;
+        }
+
+        Ok(())
+    }
+
     /// Stop watching the line
     pub fn unwatch(&mut self) {
         if let Some(ichip) = &self.ichip {
@@ -88,6 +101,22 @@ impl LineInfo {
         .map_err(Error::InvalidString)
     }
 
+    /// Get GPIO line's name, replacing any invalid UTF-8 sequences.
+    ///
+    /// Returns `None` if the line is unnamed.
+    pub fn get_name_lossy(&self) -> Option<String> {
+        let name = unsafe { bindings::gpiod_line_info_get_name(self.info) };
+        if name.is_null() {
+            return None;
+        }
+
+        // SAFETY: The string is guaranteed to be valid here.
+        let bytes =
+            unsafe { slice::from_raw_parts(name as *const u8, bindings::strlen(name) as usize) };
+
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+
     /// Returns True if the line is in use, false otherwise.
     ///
     /// The user space can't know exactly why a line is busy. It may have been
@@ -113,6 +142,22 @@ impl LineInfo {
         .map_err(Error::InvalidString)
     }
 
+    /// Get the GPIO line's consumer name, replacing any invalid UTF-8 sequences.
+    ///
+    /// Returns `None` if the line has no consumer.
+    pub fn get_consumer_lossy(&self) -> Option<String> {
+        let name = unsafe { bindings::gpiod_line_info_get_consumer(self.info) };
+        if name.is_null() {
+            return None;
+        }
+
+        // SAFETY: The string is guaranteed to be valid here.
+        let bytes =
+            unsafe { slice::from_raw_parts(name as *const u8, bindings::strlen(name) as usize) };
+
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+
     /// Get the GPIO line's direction.
     pub fn get_direction(&self) -> Result<Direction> {
         Direction::new(unsafe { bindings::gpiod_line_info_get_direction(self.info) } as u32)
@@ -143,6 +188,12 @@ impl LineInfo {
         EventClock::new(unsafe { bindings::gpiod_line_info_get_event_clock(self.info) } as u32)
     }
 
+    /// Returns true if edge event timestamps are read from the realtime clock,
+    /// false for the monotonic clock.
+    pub fn uses_realtime_clock(&self) -> Result<bool> {
+        Ok(self.get_event_clock()? == EventClock::Realtime)
+    }
+
     /// Returns true if the line is debounced (either by hardware or by the
     /// kernel software debouncer), false otherwise.
     pub fn is_debounced(&self) -> bool {
@@ -155,6 +206,280 @@ impl LineInfo {
             bindings::gpiod_line_info_get_debounce_period_us(self.info)
         })
     }
+
+    /// Get a human-readable, single line summary of the line info.
+    ///
+    /// Contains the offset, name, consumer, direction and active-low setting
+    /// of the line, followed by the bias, drive and edge detection settings in
+    /// square brackets, if they differ from the defaults. A missing name or
+    /// consumer is reported as "unnamed" or "unused".
+    pub fn summary(&self) -> String {
+        let name = match self.get_name_lossy() {
+            Some(name) => format!("\"{}\"", name),
+            None => "unnamed".to_string(),
+        };
+
+        let consumer = match self.get_consumer_lossy() {
+            Some(consumer) => format!("\"{}\"", consumer),
+            None => "unused".to_string(),
+        };
+
+        let direction = match self.get_direction() {
+            Ok(Direction::AsIs) => "as-is",
+            Ok(Direction::Input) => "input",
+            Ok(Direction::Output) => "output",
+            Err(_) => "unknown",
+        };
+
+        let active_low = if self.is_active_low() {
+            "active-low"
+        } else {
+            "active-high"
+        };
+
+        let mut flags = Vec::new();
+
+        match self.get_bias() {
+            Ok(Bias::Disabled) => flags.push("bias-disabled"),
+            Ok(Bias::PullUp) => flags.push("pull-up"),
+            Ok(Bias::PullDown) => flags.push("pull-down"),
+            _ => (),
+        }
+
+        match self.get_drive() {
+            Ok(Drive::OpenDrain) => flags.push("open-drain"),
+            Ok(Drive::OpenSource) => flags.push("open-source"),
+            _ => (),
+        }
+
+        match self.get_edge_detection() {
+            Ok(Edge::Rising) => flags.push("rising-edge"),
+            Ok(Edge::Falling) => flags.push("falling-edge"),
+            Ok(Edge::Both) => flags.push("both-edges"),
+            _ => (),
+        }
+
+        let mut summary = format!(
+            "line {:>3}: {:>16} {:>16} {:>6} {:>11}",
+            self.get_offset(),
+            name,
+            consumer,
+            direction,
+            active_low
+        );
+
+        if !flags.is_empty() {
+            summary.push_str(&format!(" [{}]", flags.join(" ")));
+        }
+
+        summary
+    }
+
+    /// Copy the snapshot of the line's status.
+    ///
+    /// The copy is independent of the original: it isn't watching the line,
+    /// even if the original is, and it stays valid after the original, or the
+    /// info event it was retrieved from, is dropped.
+    pub fn try_clone(&self) -> Result<Self> {
+        let info = unsafe { bindings::gpiod_line_info_copy(self.info) };
+        if info.is_null() {
+            return Err(Error::OperationFailed(
+                "Gpio LineInfo copy",
+                IoError::last(),
+            ));
+        }
+
+        Ok(Self {
+            info,
+            ichip: None,
+            free: true,
+        })
+    }
+
+    /// Copy the line info into an owned snapshot.
+    pub fn snapshot(&self) -> Result<LineInfoSnapshot> {
+        LineInfoSnapshot::try_from(self)
+    }
+}
+
+impl TryFrom<&LineInfo> for LineInfoSnapshot {
+    type Error = Error;
+
+    /// Copy the line info into an owned snapshot.
+    ///
+    /// Fails if libgpiod reports a setting with an unexpected value.
+    fn try_from(info: &LineInfo) -> Result<Self> {
+        Ok(LineInfoSnapshot {
+            offset: info.get_offset(),
+            name: info.get_name_lossy(),
+            used: info.is_used(),
+            consumer: info.get_consumer_lossy(),
+            direction: info.get_direction()?,
+            active_low: info.is_active_low(),
+            bias: info.get_bias()?,
+            drive: info.get_drive()?,
+            edge: info.get_edge_detection()?,
+            event_clock: info.get_event_clock()?,
+            debounced: info.is_debounced(),
+            debounce_period: info.get_debounce_period(),
+        })
+    }
+}
+
+impl fmt::Display for LineInfo {
+    /// Print the summary of the line info, see `LineInfo::summary()`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.summary())
+    }
+}
+
+/// Line info snapshot
+///
+/// An owned copy of the information held by a line info object, which doesn't
+/// refer to any libgpiod object and can be freely cloned and sent across
+/// threads. Names containing invalid UTF-8 sequences are converted lossily.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineInfoSnapshot {
+    offset: u32,
+    name: Option<String>,
+    used: bool,
+    consumer: Option<String>,
+    direction: Direction,
+    active_low: bool,
+    bias: Bias,
+    drive: Drive,
+    edge: Edge,
+    event_clock: EventClock,
+    debounced: bool,
+    debounce_period: Duration,
+}
+
+impl LineInfoSnapshot {
+    /// Get the offset of the line.
+    pub fn get_offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Get the GPIO line's name, `None` if the line is unnamed.
+    pub fn get_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns True if the line was in use, false otherwise.
+    pub fn is_used(&self) -> bool {
+        self.used
+    }
+
+    /// Get the GPIO line's consumer name, `None` if the line had no consumer.
+    pub fn get_consumer(&self) -> Option<&str> {
+        self.consumer.as_deref()
+    }
+
+    /// Get the GPIO line's direction.
+    pub fn get_direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Returns true if the line was "active-low", false otherwise.
+    pub fn is_active_low(&self) -> bool {
+        self.active_low
+    }
+
+    /// Get the GPIO line's bias setting.
+    pub fn get_bias(&self) -> Bias {
+        self.bias
+    }
+
+    /// Get the GPIO line's drive setting.
+    pub fn get_drive(&self) -> Drive {
+        self.drive
+    }
+
+    /// Get the edge detection setting of the line.
+    pub fn get_edge_detection(&self) -> Edge {
+        self.edge
+    }
+
+    /// Get the event clock setting used for edge event timestamps.
+    pub fn get_event_clock(&self) -> EventClock {
+        self.event_clock
+    }
+
+    /// Returns true if the line was debounced, false otherwise.
+    pub fn is_debounced(&self) -> bool {
+        self.debounced
+    }
+
+    /// Get the debounce period of the line.
+    pub fn get_debounce_period(&self) -> Duration {
+        self.debounce_period
+    }
+
+    /// Get the configuration settings which differ between two snapshots.
+    ///
+    /// Only the settings part of the line configuration are compared, the
+    /// name and usage of the line are ignored.
+    pub fn diff(&self, other: &LineInfoSnapshot) -> Vec<Config> {
+        let mut settings = Vec::new();
+
+        if self.direction != other.direction {
+            settings.push(Config::Direction);
+        }
+        if self.edge != other.edge {
+            settings.push(Config::EdgeDetection);
+        }
+        if self.bias != other.bias {
+            settings.push(Config::Bias);
+        }
+        if self.drive != other.drive {
+            settings.push(Config::Drive);
+        }
+        if self.active_low != other.active_low {
+            settings.push(Config::ActiveLow);
+        }
+        if self.debounce_period != other.debounce_period {
+            settings.push(Config::DebouncePeriodUs);
+        }
+        if self.event_clock != other.event_clock {
+            settings.push(Config::EventClock);
+        }
+
+        settings
+    }
+}
+
+/// Line info watch guard
+///
+/// Keeps a set of lines watched for changes, see `Chip::watch_guard()`. All
+/// the lines are unwatched when the guard is dropped.
+#[derive(Debug)]
+pub struct WatchGuard {
+    infos: Vec<LineInfo>,
+}
+
+impl WatchGuard {
+    /// Start watching the lines, stops watching the lines already watched on
+    /// failure.
+    pub(crate) fn new(chip: &Chip, offsets: &[u32]) -> Result<Self> {
+        let infos = offsets
+            .iter()
+            .map(|offset| chip.watch_line_info(*offset))
+            .collect::<Result<Vec<LineInfo>>>()?;
+
+        Ok(Self { infos })
+    }
+
+    /// Get the snapshots of information about the lines, taken when the watch
+    /// started, in the order of the offsets.
+    pub fn infos(&self) -> &[LineInfo] {
+        &self.infos
+    }
+
+    /// Get the snapshot of information about the line at given offset, if it
+    /// is watched by the guard.
+    pub fn info(&self, offset: u32) -> Option<&LineInfo> {
+        self.infos.iter().find(|info| info.get_offset() == offset)
+    }
 }
 
 impl TryFrom<&InfoEvent> for LineInfo {
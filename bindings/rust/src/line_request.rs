@@ -3,23 +3,65 @@
 // Copyright 2022 Linaro Ltd. All Rights Reserved.
 //     Viresh Kumar <viresh.kumar@linaro.org>
 
-use libc::EINVAL;
-use std::os::raw::c_ulong;
-use std::sync::Arc;
-use std::time::Duration;
+use libc::{pollfd, EINVAL, ENOBUFS, ENOTSUP, POLLIN, POLLPRI};
+use std::cmp::min;
+use std::collections::HashMap;
+use std::fmt;
+use std::mem::{take, ManuallyDrop};
+use std::os::raw::{c_int, c_ulong};
+use std::os::unix::io::AsRawFd;
+#[cfg(any(feature = "async-io", feature = "calloop"))]
+use std::os::unix::io::RawFd;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
 
 use vmm_sys_util::errno::Error as IoError;
 
-use super::{bindings, ChipInternal, EdgeEventBuffer, Error, LineConfig, RequestConfig, Result};
+use super::{
+    bindings, duration_to_timeout_ns, feature_supported, poll::poll_fds, ChipInternal, Direction,
+    Edge, EdgeEvent, EdgeEventBuffer, Error, EventClock, Feature, LineConfig, LineEdgeEvent,
+    LineInfo, Monitor, Offset, OwnedEdgeEvent, RequestConfig, Result, WaitCanceller,
+    MAX_EDGE_EVENT_BUFFER_CAPACITY,
+};
+
+/// File descriptor of a line request, as registered with an event loop.
+#[cfg(any(feature = "async-io", feature = "calloop"))]
+pub(crate) struct RequestFd(pub(crate) RawFd);
+
+#[cfg(any(feature = "async-io", feature = "calloop"))]
+impl AsRawFd for RequestFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
 
 /// Line request operations
 ///
 /// Allows interaction with a set of requested lines.
-#[derive(Debug)]
+///
+/// A request made on a chip keeps a reference to it, to read the line info of
+/// the requested lines: the file descriptor of the chip stays open until the
+/// request is dropped, even if the chip itself was closed or dropped before.
 pub struct LineRequest {
     request: *mut bindings::gpiod_line_request,
+    offsets: Vec<u32>,
+    events_read: AtomicU64,
+    // The chip the lines were requested on, to read their line info. Keeps the
+    // chip open as long as the request lives.
+    ichip: Option<Arc<ChipInternal>>,
+    event_buffer: Option<Mutex<EdgeEventBuffer>>,
 }
 
+// SAFETY: libgpiod doesn't modify the line request object after creating it,
+// all operations on it are system calls on its file descriptor, which the
+// kernel serializes. The chip is only used to read line info the same way. The
+// edge event buffer is only accessed with its mutex held, and no event
+// referring to it outlives the lock.
+unsafe impl Send for LineRequest {}
+unsafe impl Sync for LineRequest {}
+
 impl LineRequest {
     /// Request a set of lines for exclusive usage.
     pub(crate) fn new(
@@ -32,43 +74,203 @@ impl LineRequest {
         };
 
         if request.is_null() {
-            return Err(Error::OperationFailed(
+            let err = IoError::last();
+
+            // Older kernels reject the realtime clock as an invalid flag.
+            if err.errno() == EINVAL
+                && lconfig.uses_event_clock(EventClock::Realtime)
+                && !feature_supported(Feature::RealtimeEventClock)
+            {
+                return Err(Error::FeatureNotSupported(
+                    "Gpio LineRequest request-lines",
+                    Feature::RealtimeEventClock,
+                ));
+            }
+
+            return Err(Error::device_op_failed(
                 "Gpio LineRequest request-lines",
-                IoError::last(),
+                err,
             ));
         }
 
-        Ok(Self { request })
+        let mut request = unsafe { Self::from_raw(request) };
+
+        request.ichip = Some(ichip.clone());
+        Ok(request)
+    }
+
+    /// Create a line request from a raw libgpiod line request object.
+    ///
+    /// The returned `LineRequest` takes ownership of the object and releases
+    /// it when dropped.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a valid, non-null pointer to a line request obtained from
+    /// libgpiod (for example with `gpiod_chip_request_lines()` or
+    /// [`LineRequest::into_raw`]) that hasn't been released yet. Ownership of
+    /// the object is transferred, the caller must not release it or use it in
+    /// any way after this call, and must not pass the same pointer to
+    /// `from_raw` more than once.
+    pub unsafe fn from_raw(ptr: *mut bindings::gpiod_line_request) -> Self {
+        let num_lines = bindings::gpiod_line_request_get_num_lines(ptr) as usize;
+        let mut offsets = vec![0; num_lines];
+
+        bindings::gpiod_line_request_get_offsets(ptr, offsets.as_mut_ptr());
+
+        Self {
+            request: ptr,
+            offsets,
+            events_read: AtomicU64::new(0),
+            ichip: None,
+            event_buffer: None,
+        }
+    }
+
+    /// Convert the line request into a raw libgpiod line request object.
+    ///
+    /// The requested lines stay requested, the `LineRequest` relinquishes
+    /// ownership of the object and won't release it.
+    ///
+    /// The caller becomes responsible for the object, it must eventually be
+    /// released with `gpiod_line_request_release()` or converted back with
+    /// [`LineRequest::from_raw`], otherwise the lines stay requested until
+    /// the process exits.
+    pub fn into_raw(self) -> *mut bindings::gpiod_line_request {
+        let mut request = ManuallyDrop::new(self);
+
+        // Only the cached offsets, chip and event buffer are released, the line
+        // request is skipped.
+        drop(take(&mut request.offsets));
+        drop(request.ichip.take());
+        drop(request.event_buffer.take());
+        request.request
+    }
+
+    /// Release the requested lines immediately.
+    ///
+    /// This is equivalent to dropping the request, and makes early release
+    /// explicit at the call site. libgpiod doesn't report errors on release.
+    pub fn release(self) {
+        let request = self.into_raw();
+
+        unsafe { bindings::gpiod_line_request_release(request) }
+    }
+
+    /// Get the underlying libgpiod line request object.
+    ///
+    /// # Safety
+    ///
+    /// This is an escape hatch for calling libgpiod functions not wrapped by
+    /// this crate. The pointer is owned by the `LineRequest`, it must not be
+    /// released by the caller and must not be used after the `LineRequest` is
+    /// dropped.
+    pub unsafe fn as_raw_ptr(&self) -> *mut bindings::gpiod_line_request {
+        self.request
     }
 
     /// Get the number of lines in the request.
     pub fn get_num_lines(&self) -> u32 {
-        unsafe { bindings::gpiod_line_request_get_num_lines(self.request) as u32 }
+        self.offsets.len() as u32
+    }
+
+    /// Get the number of lines in the request.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns true if the request doesn't contain any lines.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
     }
 
     /// Get the offsets of lines in the request.
+    ///
+    /// The offsets are in the order they were requested in, which is the order
+    /// of the values read or set for all the lines of the request.
     pub fn get_offsets(&self) -> Vec<u32> {
-        let mut offsets = vec![0; self.get_num_lines() as usize];
+        self.offsets.clone()
+    }
+
+    /// Get the offsets of lines in the request, sorted in ascending order.
+    ///
+    /// Values are still ordered as in `get_offsets()`, not as the sorted
+    /// offsets.
+    pub fn offsets_sorted(&self) -> Vec<u32> {
+        let mut offsets = self.offsets.clone();
 
-        unsafe { bindings::gpiod_line_request_get_offsets(self.request, offsets.as_mut_ptr()) };
+        offsets.sort_unstable();
         offsets
     }
 
+    /// Returns true if the line at given offset is part of the request.
+    pub fn contains_offset(&self, offset: u32) -> bool {
+        self.offsets.contains(&offset)
+    }
+
     /// Get the value (0 or 1) of a single line associated with the request.
-    pub fn get_value(&self, offset: u32) -> Result<u32> {
+    pub fn get_value(&self, offset: impl Into<Offset>) -> Result<u32> {
+        let Offset(offset) = offset.into();
+        self.check_offset(offset)?;
+
         let value = unsafe { bindings::gpiod_line_request_get_value(self.request, offset) };
 
-        if value != 0 && value != 1 {
-            Err(Error::OperationFailed(
+        Self::value_from_ret(value)
+    }
+
+    /// Get the physical level of a single line associated with the request.
+    ///
+    /// Unlike `get_value()`, which returns the logical value, the value is
+    /// inverted back if the line is configured as active-low, as reported by
+    /// its line info. Fails for requests created with `from_raw()`, as the
+    /// chip the line belongs to isn't known.
+    pub fn get_value_raw(&self, offset: u32) -> Result<u32> {
+        let ichip = self.ichip("Gpio LineRequest get-value-raw")?;
+        let value = self.get_value(offset)?;
+
+        if LineInfo::new(ichip.clone(), offset, false)?.is_active_low() {
+            Ok(value ^ 1)
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Get the current direction of a line associated with the request.
+    ///
+    /// The direction is read from the line info, through the chip the request
+    /// was made on, which the request keeps a reference to. Fails for requests
+    /// created with `from_raw()`, as the chip the line belongs to isn't known.
+    pub fn direction(&self, offset: u32) -> Result<Direction> {
+        let ichip = self.ichip("Gpio LineRequest direction")?;
+        self.check_offset(offset)?;
+
+        LineInfo::new(ichip.clone(), offset, false)?.get_direction()
+    }
+
+    /// Private helper, Checks that the offset is part of the request.
+    fn check_offset(&self, offset: u32) -> Result<()> {
+        if self.contains_offset(offset) {
+            Ok(())
+        } else {
+            Err(Error::OffsetNotRequested(offset))
+        }
+    }
+
+    /// Private helper, Maps the value returned by libgpiod to a result.
+    fn value_from_ret(value: c_int) -> Result<u32> {
+        match value {
+            0 | 1 => Ok(value as u32),
+            -1 => Err(Error::device_op_failed(
                 "Gpio LineRequest get-value",
                 IoError::last(),
-            ))
-        } else {
-            Ok(value as u32)
+            )),
+            _ => Err(Error::InvalidValue("line", value as u32)),
         }
     }
 
     /// Get values of a subset of lines associated with the request.
+    ///
+    /// Fails if an offset isn't part of the request.
     pub fn get_values_subset(&self, offsets: &[u32], values: &mut Vec<i32>) -> Result<()> {
         if offsets.len() != values.len() {
             return Err(Error::OperationFailed(
@@ -77,6 +279,10 @@ impl LineRequest {
             ));
         }
 
+        for offset in offsets {
+            self.check_offset(*offset)?;
+        }
+
         let ret = unsafe {
             bindings::gpiod_line_request_get_values_subset(
                 self.request,
@@ -87,7 +293,7 @@ impl LineRequest {
         };
 
         if ret == -1 {
-            Err(Error::OperationFailed(
+            Err(Error::device_op_failed(
                 "Gpio LineRequest get-values-subset",
                 IoError::last(),
             ))
@@ -96,9 +302,17 @@ impl LineRequest {
         }
     }
 
+    /// Get values of a subset of lines associated with the request as booleans.
+    pub fn get_values_subset_bool(&self, offsets: &[u32]) -> Result<Vec<bool>> {
+        let mut values = vec![0; offsets.len()];
+
+        self.get_values_subset(offsets, &mut values)?;
+        Ok(values.iter().map(|value| *value != 0).collect())
+    }
+
     /// Get values of all lines associated with the request.
     pub fn get_values(&self, values: &mut Vec<i32>) -> Result<()> {
-        if values.len() != self.get_num_lines() as usize {
+        if values.len() != self.offsets.len() {
             return Err(Error::OperationFailed(
                 "Gpio LineRequest array size mismatch",
                 IoError::new(EINVAL),
@@ -109,7 +323,7 @@ impl LineRequest {
             unsafe { bindings::gpiod_line_request_get_values(self.request, values.as_mut_ptr()) };
 
         if ret == -1 {
-            Err(Error::OperationFailed(
+            Err(Error::device_op_failed(
                 "Gpio LineRequest get-values",
                 IoError::last(),
             ))
@@ -118,12 +332,35 @@ impl LineRequest {
         }
     }
 
+    /// Get values of all lines associated with the request, as a map of
+    /// offsets to values.
+    pub fn get_values_map(&self) -> Result<HashMap<u32, i32>> {
+        let mut values = vec![0; self.offsets.len()];
+
+        self.get_values(&mut values)?;
+        Ok(self.offsets.iter().cloned().zip(values).collect())
+    }
+
+    /// Get values of all lines associated with the request as booleans.
+    ///
+    /// The values are in the same order as the offsets returned by
+    /// `get_offsets()`.
+    pub fn get_values_bool(&self) -> Result<Vec<bool>> {
+        let mut values = vec![0; self.offsets.len()];
+
+        self.get_values(&mut values)?;
+        Ok(values.iter().map(|value| *value != 0).collect())
+    }
+
     /// Set the value of a single line associated with the request.
-    pub fn set_value(&self, offset: u32, value: i32) -> Result<()> {
+    pub fn set_value(&self, offset: impl Into<Offset>, value: i32) -> Result<()> {
+        let Offset(offset) = offset.into();
+        self.check_offset(offset)?;
+
         let ret = unsafe { bindings::gpiod_line_request_set_value(self.request, offset, !!value) };
 
         if ret == -1 {
-            Err(Error::OperationFailed(
+            Err(Error::device_op_failed(
                 "Gpio LineRequest set-value",
                 IoError::last(),
             ))
@@ -132,7 +369,10 @@ impl LineRequest {
         }
     }
 
-    /// Get values of a subset of lines associated with the request.
+    /// Set values of a subset of lines associated with the request.
+    ///
+    /// Fails, without setting any value, if an offset isn't part of the
+    /// request.
     pub fn set_values_subset(&self, offsets: &[u32], values: &[i32]) -> Result<()> {
         if offsets.len() != values.len() {
             return Err(Error::OperationFailed(
@@ -141,6 +381,10 @@ impl LineRequest {
             ));
         }
 
+        for offset in offsets {
+            self.check_offset(*offset)?;
+        }
+
         let ret = unsafe {
             bindings::gpiod_line_request_set_values_subset(
                 self.request,
@@ -151,7 +395,7 @@ impl LineRequest {
         };
 
         if ret == -1 {
-            Err(Error::OperationFailed(
+            Err(Error::device_op_failed(
                 "Gpio LineRequest set-values-subset",
                 IoError::last(),
             ))
@@ -160,9 +404,16 @@ impl LineRequest {
         }
     }
 
+    /// Set values of a subset of lines associated with the request from booleans.
+    pub fn set_values_subset_bool(&self, offsets: &[u32], values: &[bool]) -> Result<()> {
+        let values: Vec<i32> = values.iter().map(|value| *value as i32).collect();
+
+        self.set_values_subset(offsets, &values)
+    }
+
     /// Get values of all lines associated with the request.
     pub fn set_values(&self, values: &[i32]) -> Result<()> {
-        if values.len() != self.get_num_lines() as usize {
+        if values.len() != self.offsets.len() {
             return Err(Error::OperationFailed(
                 "Gpio LineRequest array size mismatch",
                 IoError::new(EINVAL),
@@ -172,7 +423,7 @@ impl LineRequest {
         let ret = unsafe { bindings::gpiod_line_request_set_values(self.request, values.as_ptr()) };
 
         if ret == -1 {
-            Err(Error::OperationFailed(
+            Err(Error::device_op_failed(
                 "Gpio LineRequest set-values",
                 IoError::last(),
             ))
@@ -181,6 +432,77 @@ impl LineRequest {
         }
     }
 
+    /// Set values of a subset of lines associated with the request, from a
+    /// map of offsets to values.
+    ///
+    /// Fails, without setting any value, if an offset isn't part of the
+    /// request.
+    pub fn set_values_map(&self, values: &HashMap<u32, i32>) -> Result<()> {
+        let (offsets, values): (Vec<u32>, Vec<i32>) = values.iter().unzip();
+
+        self.set_values_subset(&offsets, &values)
+    }
+
+    /// Set values of a subset of lines associated with the request, from
+    /// pairs of offsets and values.
+    ///
+    /// Fails, without setting any value, if an offset isn't part of the
+    /// request.
+    pub fn set_values_iter(&self, pairs: impl IntoIterator<Item = (u32, i32)>) -> Result<()> {
+        let (offsets, values): (Vec<u32>, Vec<i32>) = pairs.into_iter().unzip();
+
+        self.set_values_subset(&offsets, &values)
+    }
+
+    /// Generate a square wave on a line.
+    ///
+    /// Drives the line high for `high`, then low for `low`, repeating this
+    /// `cycles` times, and leaves the line low at the end. Blocks until done,
+    /// returns early on errors.
+    pub fn pulse(&self, offset: u32, high: Duration, low: Duration, cycles: u32) -> Result<()> {
+        for _ in 0..cycles {
+            self.set_value(offset, 1)?;
+            sleep(high);
+            self.set_value(offset, 0)?;
+            sleep(low);
+        }
+
+        self.set_value(offset, 0)
+    }
+
+    /// Set all the lines associated with the request to the same value.
+    pub fn set_all(&self, value: i32) -> Result<()> {
+        self.set_values(&vec![value; self.offsets.len()])
+    }
+
+    /// Get values of all lines associated with the request as a bitmask.
+    ///
+    /// Bit `i` is set if the `i`-th line of the request is active, the bit
+    /// positions follow the order of `get_offsets()`, not the offsets of the
+    /// lines on the chip. A request holds at most 64 lines, so they all fit.
+    pub fn get_value_bitmask(&self) -> Result<u64> {
+        let values = self.get_values_bool()?;
+
+        Ok(values
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value)
+            .fold(0, |mask, (index, _)| mask | (1 << index)))
+    }
+
+    /// Set values of all lines associated with the request from a bitmask.
+    ///
+    /// The `i`-th line of the request is set active if bit `i` is set, the bit
+    /// positions follow the order of `get_offsets()`. Bits beyond the number of
+    /// lines in the request are ignored.
+    pub fn set_value_bitmask(&self, mask: u64) -> Result<()> {
+        let values: Vec<i32> = (0..self.offsets.len())
+            .map(|index| ((mask >> index) & 1) as i32)
+            .collect();
+
+        self.set_values(&values)
+    }
+
     /// Update the configuration of lines associated with the line request.
     pub fn reconfigure_lines(&self, lconfig: &LineConfig) -> Result<()> {
         let ret = unsafe {
@@ -188,7 +510,7 @@ impl LineRequest {
         };
 
         if ret == -1 {
-            Err(Error::OperationFailed(
+            Err(Error::device_op_failed(
                 "Gpio LineRequest reconfigure-lines",
                 IoError::last(),
             ))
@@ -197,6 +519,81 @@ impl LineRequest {
         }
     }
 
+    /// Change the direction of a single line associated with the request.
+    ///
+    /// The current settings of all the lines of the request, as reported by
+    /// the kernel, are preserved otherwise. A line switched to output keeps
+    /// driving the value it last read, and its edge detection is disabled.
+    /// Fails for requests created with `from_raw()`, as the chip the lines
+    /// belong to isn't known.
+    pub fn set_direction(&self, offset: u32, direction: Direction) -> Result<()> {
+        self.check_offset(offset)?;
+
+        let mut lconfig = self.current_config("Gpio LineRequest set-direction")?;
+
+        if direction == Direction::Output {
+            lconfig.set_output_value_override(self.get_value(offset)?, offset);
+            lconfig.set_edge_detection_override(Edge::None, offset);
+        }
+
+        lconfig.set_direction_override(direction, offset);
+        self.reconfigure_lines(&lconfig)
+    }
+
+    /// Change the edge detection of a subset of lines associated with the
+    /// request.
+    ///
+    /// The current settings of all the lines of the request, as reported by
+    /// the kernel, are preserved otherwise. Fails for requests created with
+    /// `from_raw()`, as the chip the lines belong to isn't known.
+    pub fn set_edge_detection(&self, offsets: &[u32], edge: Edge) -> Result<()> {
+        for offset in offsets {
+            self.check_offset(*offset)?;
+        }
+
+        let mut lconfig = self.current_config("Gpio LineRequest set-edge-detection")?;
+
+        lconfig.set_edge_detection_overrides(edge, offsets);
+        self.reconfigure_lines(&lconfig)
+    }
+
+    /// Private helper, Returns the chip the lines were requested from.
+    fn ichip(&self, op: &'static str) -> Result<&Arc<ChipInternal>> {
+        match &self.ichip {
+            Some(ichip) => Ok(ichip),
+            None => Err(Error::OperationFailed(op, IoError::new(ENOTSUP))),
+        }
+    }
+
+    /// Private helper, Returns a line config reproducing the current settings
+    /// of the lines of the request.
+    fn current_config(&self, op: &'static str) -> Result<LineConfig> {
+        let ichip = self.ichip(op)?;
+        let mut lconfig = LineConfig::new()?;
+        let mut values = vec![0; self.offsets.len()];
+
+        self.get_values(&mut values)?;
+
+        for (offset, value) in self.offsets.iter().cloned().zip(values) {
+            let info = LineInfo::new(ichip.clone(), offset, false)?;
+            let direction = info.get_direction()?;
+
+            lconfig.set_direction_override(direction, offset);
+            lconfig.set_edge_detection_override(info.get_edge_detection()?, offset);
+            lconfig.set_bias_override(info.get_bias()?, offset);
+            lconfig.set_drive_override(info.get_drive()?, offset);
+            lconfig.set_active_low_override(info.is_active_low(), offset);
+            lconfig.set_debounce_period_override(info.get_debounce_period(), offset);
+            lconfig.set_event_clock_override(info.get_event_clock()?, offset);
+
+            if direction == Direction::Output {
+                lconfig.set_output_value_override(value as u32, offset);
+            }
+        }
+
+        Ok(lconfig)
+    }
+
     /// Get the file descriptor associated with the line request.
     pub fn get_fd(&self) -> u32 {
         unsafe { bindings::gpiod_line_request_get_fd(self.request) as u32 }
@@ -205,11 +602,14 @@ impl LineRequest {
     /// Wait for edge events on any of the lines associated with the request.
     pub fn wait_edge_event(&self, timeout: Duration) -> Result<()> {
         let ret = unsafe {
-            bindings::gpiod_line_request_wait_edge_event(self.request, timeout.as_nanos() as i64)
+            bindings::gpiod_line_request_wait_edge_event(
+                self.request,
+                duration_to_timeout_ns(timeout),
+            )
         };
 
         match ret {
-            -1 => Err(Error::OperationFailed(
+            -1 => Err(Error::device_op_failed(
                 "Gpio LineRequest edge-event-wait",
                 IoError::last(),
             )),
@@ -218,6 +618,75 @@ impl LineRequest {
         }
     }
 
+    /// Poll for edge events on any of the lines associated with the request.
+    ///
+    /// Same as `wait_edge_event()`, but a timeout isn't an error: returns true
+    /// if events are pending, false if the timeout expired.
+    pub fn poll_edge_event(&self, timeout: Duration) -> Result<bool> {
+        match self.wait_edge_event(timeout) {
+            Ok(()) => Ok(true),
+            Err(Error::OperationTimedOut) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Check if edge events are pending on the request, without reading them.
+    ///
+    /// Neither libgpiod nor the kernel expose the length of the event queue,
+    /// the line request file descriptor doesn't support `FIONREAD`. This polls
+    /// the request without blocking instead.
+    pub fn has_pending_edge_events(&self) -> Result<bool> {
+        self.poll_edge_event(Duration::from_nanos(0))
+    }
+
+    /// Discard all edge events currently pending on the request.
+    ///
+    /// Reads, and drops, events until none are left, returning the number of
+    /// events discarded.
+    pub fn drain_edge_events(&self) -> Result<usize> {
+        let buffer = EdgeEventBuffer::new(0)?;
+        let mut count = 0;
+
+        while self.poll_edge_event(Duration::from_nanos(0))? {
+            count += self.read_edge_event(&buffer, buffer.get_capacity())? as usize;
+        }
+
+        Ok(count)
+    }
+
+    /// Wait for edge events on any of the lines associated with the request,
+    /// or until the wait is cancelled.
+    ///
+    /// Blocks indefinitely if `timeout` is `None`. Returns `Error::Cancelled`
+    /// if the canceller is signalled, even if edge events are pending as well.
+    pub fn wait_edge_event_cancellable(
+        &self,
+        timeout: Option<Duration>,
+        canceller: &WaitCanceller,
+    ) -> Result<()> {
+        let mut fds = [
+            pollfd {
+                fd: self.get_fd() as c_int,
+                events: POLLIN | POLLPRI,
+                revents: 0,
+            },
+            pollfd {
+                fd: canceller.as_raw_fd(),
+                events: POLLIN,
+                revents: 0,
+            },
+        ];
+        match poll_fds(&mut fds, timeout) {
+            Err(err) => Err(Error::device_op_failed(
+                "Gpio LineRequest edge-event-wait-cancellable",
+                err,
+            )),
+            Ok(0) => Err(Error::OperationTimedOut),
+            Ok(_) if fds[1].revents != 0 => Err(Error::Cancelled),
+            Ok(_) => Ok(()),
+        }
+    }
+
     /// Get a number of edge events from a line request.
     ///
     /// This function will block if no event was queued for the line.
@@ -231,14 +700,204 @@ impl LineRequest {
         };
 
         if ret == -1 {
-            Err(Error::OperationFailed(
+            Err(Error::device_op_failed(
                 "Gpio LineRequest edge-event-read",
                 IoError::last(),
             ))
         } else {
+            self.events_read.fetch_add(ret as u64, Ordering::Relaxed);
             Ok(ret as u32)
         }
     }
+
+    /// Read up to `max_events` edge events, returned as owned copies.
+    ///
+    /// The events don't refer to any edge event buffer and can be sent to
+    /// other threads. This function will block if no event was queued for the
+    /// lines.
+    pub fn read_edge_events_owned(&self, max_events: usize) -> Result<Vec<OwnedEdgeEvent>> {
+        if max_events == 0 {
+            return Ok(Vec::new());
+        }
+
+        let max_events = min(max_events, MAX_EDGE_EVENT_BUFFER_CAPACITY as usize) as u32;
+        let buffer = EdgeEventBuffer::new(max_events)?;
+        let count = self.read_edge_event(&buffer, min(max_events, buffer.get_capacity()))?;
+
+        (0..count)
+            .map(|index| buffer.get_event(index as u64)?.to_owned())
+            .collect()
+    }
+
+    /// Wait for edge events and read them into the buffer in a single call.
+    ///
+    /// Returns the number of events read, up to `max_events`, or 0 if the
+    /// timeout expired without any event.
+    pub fn next_edge_events(
+        &self,
+        timeout: Duration,
+        buffer: &EdgeEventBuffer,
+        max_events: u32,
+    ) -> Result<u32> {
+        if !self.poll_edge_event(timeout)? {
+            return Ok(0);
+        }
+
+        self.read_edge_event(buffer, max_events)
+    }
+
+    /// Get a number of edge events from a line request, waiting for them at
+    /// most `timeout`.
+    ///
+    /// Same as `next_edge_events()`, with the arguments ordered as in
+    /// `read_edge_event()`: returns 0 if no event was queued before the
+    /// timeout expired.
+    pub fn read_edge_event_timeout(
+        &self,
+        buffer: &EdgeEventBuffer,
+        max_events: u32,
+        timeout: Duration,
+    ) -> Result<u32> {
+        self.next_edge_events(timeout, buffer, max_events)
+    }
+
+    /// Store an edge event buffer of given capacity in the request, to be
+    /// reused by `read_buffered_edge_events()`.
+    ///
+    /// The buffer is guarded by a mutex, concurrent buffered reads from
+    /// multiple threads are serialized. Use separate buffers with
+    /// `read_edge_event()` instead if that's a concern.
+    pub fn with_event_buffer(mut self, capacity: u32) -> Result<Self> {
+        self.event_buffer = Some(Mutex::new(EdgeEventBuffer::new(capacity)?));
+        Ok(self)
+    }
+
+    /// Read edge events into the buffer stored in the request and pass each
+    /// of them to the callback, without allocating.
+    ///
+    /// The events are only borrowed for the duration of the callback, as the
+    /// buffer is overwritten by the next read. Use `EdgeEvent::to_owned()` to
+    /// keep them. Blocks if no event was queued for the lines. Returns the
+    /// number of events read. Fails if no buffer was set with
+    /// `with_event_buffer()`.
+    pub fn read_buffered_edge_events<F>(&self, mut callback: F) -> Result<u32>
+    where
+        F: FnMut(&EdgeEvent),
+    {
+        let buffer = match &self.event_buffer {
+            Some(buffer) => buffer,
+            None => {
+                return Err(Error::OperationFailed(
+                    "Gpio LineRequest buffered-edge-event-read",
+                    IoError::new(ENOBUFS),
+                ))
+            }
+        };
+        // The buffer is overwritten by every read, a poisoned lock is harmless.
+        let buffer = buffer.lock().unwrap_or_else(|err| err.into_inner());
+        let count = self.read_edge_event(&buffer, buffer.get_capacity())?;
+
+        for index in 0..count {
+            callback(&buffer.get_event(index as u64)?);
+        }
+
+        Ok(count)
+    }
+
+    /// Get the total number of edge events read through the request.
+    pub fn total_events_read(&self) -> u64 {
+        self.events_read.load(Ordering::Relaxed)
+    }
+
+    /// Read edge events until `target` events were read or the timeout
+    /// expires, whichever comes first.
+    ///
+    /// Each read replaces the contents of the buffer, which is only used as
+    /// scratch space here: copies of the events read are returned instead.
+    /// Fewer than `target` events are returned if the timeout expires.
+    pub fn read_edge_events_until(
+        &self,
+        buffer: &EdgeEventBuffer,
+        target: u32,
+        timeout: Duration,
+    ) -> Result<Vec<EdgeEvent>> {
+        let deadline = Instant::now() + timeout;
+        let mut events = Vec::with_capacity(target as usize);
+
+        while (events.len() as u32) < target {
+            let remaining = match deadline.checked_duration_since(Instant::now()) {
+                Some(remaining) => remaining,
+                None => break,
+            };
+
+            match self.wait_edge_event(remaining) {
+                Err(Error::OperationTimedOut) => break,
+                Err(err) => return Err(err),
+                Ok(()) => (),
+            }
+
+            let max_events = min(target - events.len() as u32, buffer.get_capacity());
+            let count = self.read_edge_event(buffer, max_events)?;
+
+            for index in 0..count {
+                events.push(buffer.get_event_copy(index as u64)?);
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Wait for an edge event of the given type on a line.
+    ///
+    /// Events are read one at a time, the events which don't match the offset
+    /// or the type are consumed and discarded, the ones queued after the
+    /// matching event are left in place. Fails with `OperationTimedOut` if no
+    /// matching event arrives before the timeout expires.
+    pub fn wait_for_edge(
+        &self,
+        offset: u32,
+        edge: LineEdgeEvent,
+        timeout: Duration,
+    ) -> Result<EdgeEvent> {
+        self.check_offset(offset)?;
+        let deadline = Instant::now() + timeout;
+        let buffer = EdgeEventBuffer::new(1)?;
+
+        loop {
+            let remaining = deadline
+                .checked_duration_since(Instant::now())
+                .ok_or(Error::OperationTimedOut)?;
+
+            self.wait_edge_event(remaining)?;
+            if self.read_edge_event(&buffer, 1)? == 0 {
+                continue;
+            }
+
+            let event = buffer.get_event_copy(0)?;
+            if event.get_line_offset() == offset && event.get_event_type()? == edge {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Start monitoring edge events on a background thread.
+    ///
+    /// Copies of the edge events are sent to the channel exposed by the
+    /// returned `Monitor`, which stops the thread when dropped.
+    pub fn spawn_monitor(self: Arc<Self>) -> Monitor {
+        Monitor::new(self)
+    }
+}
+
+impl fmt::Debug for LineRequest {
+    /// Print the offsets and the number of the requested lines, from the
+    /// cached offsets.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LineRequest")
+            .field("offsets", &self.offsets)
+            .field("num_lines", &self.offsets.len())
+            .finish()
+    }
 }
 
 impl Drop for LineRequest {
@@ -247,3 +906,18 @@ impl Drop for LineRequest {
         unsafe { bindings::gpiod_line_request_release(self.request) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unexpected_value() {
+        assert_eq!(LineRequest::value_from_ret(0).unwrap(), 0);
+        assert_eq!(LineRequest::value_from_ret(1).unwrap(), 1);
+
+        let err = LineRequest::value_from_ret(2).unwrap_err();
+        assert_eq!(err, Error::InvalidValue("line", 2));
+        assert_eq!(err.to_string(), "Invalid line value: 2");
+    }
+}
@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{spawn, JoinHandle};
+use std::time::Duration;
+
+use super::{EdgeEventBuffer, Error, LineRequest, OwnedEdgeEvent, Result};
+
+/// Interval after which the monitor thread checks if it needs to stop.
+const MONITOR_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Line edge events monitor
+///
+/// Runs the wait/read loop for edge events of a line request on a background
+/// thread and forwards owned copies of the events over a channel. The thread stops
+/// when the monitor is dropped, the receiving end of the channel is gone or
+/// an error is encountered (which is forwarded before stopping).
+pub struct Monitor {
+    receiver: Receiver<Result<OwnedEdgeEvent>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Monitor {
+    /// Start monitoring edge events on the line request.
+    pub(crate) fn new(request: Arc<LineRequest>) -> Self {
+        let (sender, receiver) = channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+
+        let handle = spawn(move || run(&request, &sender, &thread_stop));
+
+        Self {
+            receiver,
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Get the receiving end of the channel the edge events are sent to.
+    pub fn receiver(&self) -> &Receiver<Result<OwnedEdgeEvent>> {
+        &self.receiver
+    }
+}
+
+/// Monitor thread body, returns once the monitor needs to stop.
+fn run(request: &LineRequest, sender: &Sender<Result<OwnedEdgeEvent>>, stop: &AtomicBool) {
+    let buffer = match EdgeEventBuffer::new(0) {
+        Ok(buffer) => buffer,
+        Err(err) => {
+            let _ = sender.send(Err(err));
+            return;
+        }
+    };
+
+    while !stop.load(Ordering::Relaxed) {
+        match request.wait_edge_event(MONITOR_POLL_INTERVAL) {
+            Err(Error::OperationTimedOut) => continue,
+            Err(err) => {
+                let _ = sender.send(Err(err));
+                return;
+            }
+            Ok(()) => (),
+        }
+
+        let count = match request.read_edge_event(&buffer, buffer.get_capacity()) {
+            Ok(count) => count,
+            Err(err) => {
+                let _ = sender.send(Err(err));
+                return;
+            }
+        };
+
+        for index in 0..count {
+            let event = buffer
+                .get_event(index as u64)
+                .and_then(|event| event.to_owned());
+
+            if sender.send(event).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl Drop for Monitor {
+    /// Stop the monitor thread and wait for it to finish.
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+use libc::{nfds_t, pollfd, ppoll, time_t, timespec, POLLIN, POLLPRI};
+use std::cmp::min;
+use std::os::raw::c_long;
+use std::os::unix::io::{AsRawFd, BorrowedFd};
+use std::ptr;
+use std::time::Duration;
+
+use vmm_sys_util::errno::Error as IoError;
+
+use super::{Error, Result};
+
+/// Private helper, Polls the file descriptors, blocking indefinitely if
+/// `timeout` is `None`. Returns the number of ready file descriptors, 0 on
+/// timeout.
+pub(crate) fn poll_fds(
+    fds: &mut [pollfd],
+    timeout: Option<Duration>,
+) -> std::result::Result<usize, IoError> {
+    let ts = timeout.map(|timeout| timespec {
+        tv_sec: min(timeout.as_secs(), time_t::MAX as u64) as time_t,
+        tv_nsec: timeout.subsec_nanos() as c_long,
+    });
+    let ts_ptr = ts.as_ref().map_or(ptr::null(), |ts| ts as *const timespec);
+
+    let ret = unsafe { ppoll(fds.as_mut_ptr(), fds.len() as nfds_t, ts_ptr, ptr::null()) };
+
+    if ret == -1 {
+        Err(IoError::last())
+    } else {
+        Ok(ret as usize)
+    }
+}
+
+/// Wait for a file descriptor to become readable.
+///
+/// Works with any file descriptor, e.g. one of a line request or a chip
+/// obtained elsewhere. Blocks indefinitely if `timeout` is `None`. Returns
+/// true if the file descriptor is readable, false if the timeout expired.
+pub fn wait_readable(fd: BorrowedFd<'_>, timeout: Option<Duration>) -> Result<bool> {
+    let mut fds = [pollfd {
+        fd: fd.as_raw_fd(),
+        events: POLLIN | POLLPRI,
+        revents: 0,
+    }];
+
+    match poll_fds(&mut fds, timeout) {
+        Ok(ready) => Ok(ready != 0),
+        Err(err) => Err(Error::OperationFailed("Gpio wait-readable", err)),
+    }
+}
@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+use std::time::Duration;
+
+use super::{
+    Bias, Chip, Direction, Drive, Edge, EventClock, LineConfig, LineRequest, RequestConfig, Result,
+};
+
+/// Line request builder
+///
+/// Accumulates the request config and the line config of a line request and
+/// issues it on the chip it was created from, see `Chip::request_builder()`.
+/// Same as with the config objects, the settings don't return errors. Invalid
+/// settings are reported at the time of the request.
+pub struct RequestBuilder<'a> {
+    chip: &'a Chip,
+    rconfig: RequestConfig,
+    lconfig: LineConfig,
+}
+
+impl<'a> RequestBuilder<'a> {
+    /// Create a new request builder for the chip.
+    pub(crate) fn new(chip: &'a Chip) -> Result<Self> {
+        Ok(Self {
+            chip,
+            rconfig: RequestConfig::new()?,
+            lconfig: LineConfig::new()?,
+        })
+    }
+
+    /// Set the consumer name for the request.
+    pub fn consumer(self, consumer: &str) -> Self {
+        self.rconfig.set_consumer(consumer);
+        self
+    }
+
+    /// Set the offsets of the lines to be requested.
+    pub fn offsets(self, offsets: &[u32]) -> Self {
+        self.rconfig.set_offsets(offsets);
+        self
+    }
+
+    /// Set the size of the kernel event buffer for the request.
+    pub fn event_buffer_size(self, size: u32) -> Self {
+        self.rconfig.set_event_buffer_size(size);
+        self
+    }
+
+    /// Set the default direction setting.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.lconfig.set_direction_default(direction);
+        self
+    }
+
+    /// Override the direction setting for a line.
+    pub fn direction_override(mut self, direction: Direction, offset: u32) -> Self {
+        self.lconfig.set_direction_override(direction, offset);
+        self
+    }
+
+    /// Set the default edge event detection setting.
+    pub fn edge_detection(mut self, edge: Edge) -> Self {
+        self.lconfig.set_edge_detection_default(edge);
+        self
+    }
+
+    /// Override the edge event detection setting for a line.
+    pub fn edge_detection_override(mut self, edge: Edge, offset: u32) -> Self {
+        self.lconfig.set_edge_detection_override(edge, offset);
+        self
+    }
+
+    /// Set the default bias setting.
+    pub fn bias(mut self, bias: Bias) -> Self {
+        self.lconfig.set_bias_default(bias);
+        self
+    }
+
+    /// Override the bias setting for a line.
+    pub fn bias_override(mut self, bias: Bias, offset: u32) -> Self {
+        self.lconfig.set_bias_override(bias, offset);
+        self
+    }
+
+    /// Set the default drive setting.
+    pub fn drive(mut self, drive: Drive) -> Self {
+        self.lconfig.set_drive_default(drive);
+        self
+    }
+
+    /// Override the drive setting for a line.
+    pub fn drive_override(mut self, drive: Drive, offset: u32) -> Self {
+        self.lconfig.set_drive_override(drive, offset);
+        self
+    }
+
+    /// Set the default active-low setting.
+    pub fn active_low(mut self, active_low: bool) -> Self {
+        self.lconfig.set_active_low_default(active_low);
+        self
+    }
+
+    /// Override the active-low setting for a line.
+    pub fn active_low_override(mut self, active_low: bool, offset: u32) -> Self {
+        self.lconfig.set_active_low_override(active_low, offset);
+        self
+    }
+
+    /// Set the default debounce period setting.
+    pub fn debounce_period(mut self, period: Duration) -> Self {
+        self.lconfig.set_debounce_period_default(period);
+        self
+    }
+
+    /// Override the debounce period setting for a line.
+    pub fn debounce_period_override(mut self, period: Duration, offset: u32) -> Self {
+        self.lconfig.set_debounce_period_override(period, offset);
+        self
+    }
+
+    /// Set the default event clock setting.
+    pub fn event_clock(mut self, clock: EventClock) -> Self {
+        self.lconfig.set_event_clock_default(clock);
+        self
+    }
+
+    /// Override the event clock setting for a line.
+    pub fn event_clock_override(mut self, clock: EventClock, offset: u32) -> Self {
+        self.lconfig.set_event_clock_override(clock, offset);
+        self
+    }
+
+    /// Set the default output value setting.
+    pub fn output_value(mut self, value: u32) -> Self {
+        self.lconfig.set_output_value_default(value);
+        self
+    }
+
+    /// Override the output value setting for a line.
+    pub fn output_value_override(mut self, value: u32, offset: u32) -> Self {
+        self.lconfig.set_output_value_override(value, offset);
+        self
+    }
+
+    /// Request the lines with the accumulated configuration.
+    pub fn request(self) -> Result<LineRequest> {
+        self.chip.request_lines(&self.rconfig, &self.lconfig)
+    }
+}
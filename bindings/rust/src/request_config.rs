@@ -40,6 +40,18 @@ impl RequestConfig {
         self.config
     }
 
+    /// Get the underlying libgpiod request config object.
+    ///
+    /// # Safety
+    ///
+    /// This is an escape hatch for calling libgpiod functions not wrapped by
+    /// this crate. The pointer is owned by the `RequestConfig`, it must not be
+    /// freed by the caller and must not be used after the `RequestConfig` is
+    /// dropped.
+    pub unsafe fn as_raw_ptr(&self) -> *mut bindings::gpiod_request_config {
+        self.config
+    }
+
     /// Set the consumer name for the request.
     ///
     /// If the consumer string is too long, it will be truncated to the max
@@ -89,6 +101,36 @@ impl RequestConfig {
         }
     }
 
+    /// Set the offsets of the lines to be requested from an iterator, such as
+    /// a range or a filtered list.
+    ///
+    /// Same as `set_offsets()`, the offsets are collected internally.
+    pub fn set_offsets_iter<I: IntoIterator<Item = u32>>(&self, offsets: I) {
+        let offsets: Vec<u32> = offsets.into_iter().collect();
+
+        self.set_offsets(&offsets)
+    }
+
+    /// Append an offset to the offsets of the lines to be requested.
+    ///
+    /// Same as `set_offsets()`, offsets above the limit accepted by the kernel
+    /// (64 lines) are silently dropped.
+    ///
+    /// Each call reads back and rewrites the whole offset array, building a
+    /// request one offset at a time is quadratic. Use `set_offsets()` or
+    /// `set_offsets_iter()` to set many offsets at once.
+    pub fn push_offset(&self, offset: u32) {
+        let mut offsets = self.get_offsets();
+
+        offsets.push(offset);
+        self.set_offsets(&offsets)
+    }
+
+    /// Remove all the offsets of the lines to be requested.
+    pub fn clear_offsets(&self) {
+        self.set_offsets(&[])
+    }
+
     /// Get the offsets of lines in the request config.
     pub fn get_offsets(&self) -> Vec<u32> {
         let num = unsafe { bindings::gpiod_request_config_get_num_offsets(self.config) };
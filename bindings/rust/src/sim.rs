@@ -3,13 +3,34 @@
 // Copyright 2022 Linaro Ltd. All Rights Reserved.
 //     Viresh Kumar <viresh.kumar@linaro.org>
 
+//! GPIO simulator
+//!
+//! Wrappers around the gpio-sim kernel module, allowing to create simulated
+//! GPIO chips for testing code using libgpiod without real hardware. The
+//! gpio-sim module must be available and configfs must be mounted.
+//!
+//! ```no_run
+//! use libgpiod::{sim::Sim, Chip};
+//!
+//! let sim = Sim::new(Some(8), Some("foobar"), true)?;
+//! let chip = Chip::open(sim.dev_path()?)?;
+//!
+//! assert_eq!(chip.get_label()?, "foobar");
+//! assert_eq!(chip.get_num_lines(), 8);
+//! # Ok::<(), libgpiod::Error>(())
+//! ```
+
 use std::os::raw::c_char;
 use std::{slice, str};
 
 use vmm_sys_util::errno::Error as IoError;
 
-use libgpiod::{Error, Result};
-use libgpiod_sys as bindings;
+use super::{bindings, Error, Result};
+
+pub use bindings::{
+    GPIOSIM_HOG_DIR_INPUT, GPIOSIM_HOG_DIR_OUTPUT_HIGH, GPIOSIM_HOG_DIR_OUTPUT_LOW,
+    GPIOSIM_PULL_DOWN, GPIOSIM_PULL_UP, GPIOSIM_VALUE_ACTIVE, GPIOSIM_VALUE_INACTIVE,
+};
 
 /// Sim Ctx
 #[derive(Debug)]
@@ -97,9 +118,11 @@ impl Drop for SimDev {
     }
 }
 
-/// Sim Bank
+/// Simulated GPIO bank
+///
+/// Each bank of an enabled gpio-sim device shows up as a separate GPIO chip.
 #[derive(Debug)]
-struct SimBank {
+pub struct SimBank {
     bank: *mut bindings::gpiosim_bank,
 }
 
@@ -116,10 +139,14 @@ impl SimBank {
         Ok(Self { bank })
     }
 
-    fn chip_name(&self) -> Result<&str> {
+    /// Get the name of the GPIO chip, available once the device is enabled.
+    pub fn chip_name(&self) -> Result<&str> {
         // SAFETY: The string returned by gpiosim is guaranteed to live as long
         // as the `struct SimBank`.
         let name = unsafe { bindings::gpiosim_bank_get_chip_name(self.bank) };
+        if name.is_null() {
+            return Err(Error::NameNotFound("gpio-sim chip name"));
+        }
 
         // SAFETY: The string is guaranteed to be valid here.
         str::from_utf8(unsafe {
@@ -128,10 +155,15 @@ impl SimBank {
         .map_err(Error::InvalidString)
     }
 
-    fn dev_path(&self) -> Result<&str> {
+    /// Get the path to the GPIO chip's character device, available once the
+    /// device is enabled.
+    pub fn dev_path(&self) -> Result<&str> {
         // SAFETY: The string returned by gpiosim is guaranteed to live as long
         // as the `struct SimBank`.
         let path = unsafe { bindings::gpiosim_bank_get_dev_path(self.bank) };
+        if path.is_null() {
+            return Err(Error::NameNotFound("gpio-sim device path"));
+        }
 
         // SAFETY: The string is guaranteed to be valid here.
         str::from_utf8(unsafe {
@@ -140,7 +172,8 @@ impl SimBank {
         .map_err(Error::InvalidString)
     }
 
-    fn val(&self, offset: u32) -> Result<u32> {
+    /// Get the value of the line as driven by its user, `GPIOSIM_VALUE_*`.
+    pub fn get_value(&self, offset: u32) -> Result<u32> {
         let ret = unsafe { bindings::gpiosim_bank_get_value(self.bank, offset) };
 
         if ret == -1 {
@@ -153,7 +186,8 @@ impl SimBank {
         }
     }
 
-    fn set_label(&self, label: &str) -> Result<()> {
+    /// Set the label of the chip.
+    pub fn set_label(&self, label: &str) -> Result<()> {
         // Null-terminate the string
         let label = label.to_owned() + "\0";
 
@@ -170,7 +204,8 @@ impl SimBank {
         }
     }
 
-    fn set_num_lines(&self, num: u64) -> Result<()> {
+    /// Set the number of lines exposed by the chip.
+    pub fn set_num_lines(&self, num: u64) -> Result<()> {
         let ret = unsafe { bindings::gpiosim_bank_set_num_lines(self.bank, num) };
         if ret == -1 {
             Err(Error::OperationFailed(
@@ -182,7 +217,8 @@ impl SimBank {
         }
     }
 
-    fn set_line_name(&self, offset: u32, name: &str) -> Result<()> {
+    /// Set the name of a line.
+    pub fn set_line_name(&self, offset: u32, name: &str) -> Result<()> {
         // Null-terminate the string
         let name = name.to_owned() + "\0";
 
@@ -200,7 +236,9 @@ impl SimBank {
         }
     }
 
-    fn set_pull(&self, offset: u32, pull: i32) -> Result<()> {
+    /// Pull the line up or down, `GPIOSIM_PULL_*`, simulating an externally
+    /// driven input.
+    pub fn set_pull(&self, offset: u32, pull: i32) -> Result<()> {
         let ret = unsafe { bindings::gpiosim_bank_set_pull(self.bank, offset, pull) };
 
         if ret == -1 {
@@ -210,7 +248,9 @@ impl SimBank {
         }
     }
 
-    fn hog_line(&self, offset: u32, name: &str, dir: i32) -> Result<()> {
+    /// Hog the line with the given consumer name and direction,
+    /// `GPIOSIM_HOG_DIR_*`.
+    pub fn hog_line(&self, offset: u32, name: &str, dir: i32) -> Result<()> {
         // Null-terminate the string
         let name = name.to_owned() + "\0";
 
@@ -226,25 +266,43 @@ impl SimBank {
     }
 }
 
+impl Clone for SimBank {
+    /// Get another handle to the same bank.
+    fn clone(&self) -> Self {
+        Self {
+            bank: unsafe { bindings::gpiosim_bank_ref(self.bank) },
+        }
+    }
+}
+
 impl Drop for SimBank {
     fn drop(&mut self) {
         unsafe { bindings::gpiosim_bank_unref(self.bank) }
     }
 }
 
-/// GPIO SIM
+/// Simulated GPIO chip
+///
+/// A gpio-sim device with one or more banks, each of which shows up as a GPIO
+/// chip once enabled. Banks can only be added, and lines can only be named and
+/// hogged, while the device is disabled. The methods of `Sim` itself operate
+/// on the first bank.
 #[derive(Debug)]
-pub(crate) struct Sim {
-    ctx: SimCtx,
+pub struct Sim {
+    // Keeps the gpio-sim context alive as long as the device.
+    _ctx: SimCtx,
     dev: SimDev,
     bank: SimBank,
+    banks: Vec<SimBank>,
 }
 
 unsafe impl Send for Sim {}
 unsafe impl Sync for Sim {}
 
 impl Sim {
-    pub(crate) fn new(ngpio: Option<u64>, label: Option<&str>, enable: bool) -> Result<Self> {
+    /// Create a new simulated chip with an optional number of lines and
+    /// label, and optionally enable it right away.
+    pub fn new(ngpio: Option<u64>, label: Option<&str>, enable: bool) -> Result<Self> {
         let ctx = SimCtx::new()?;
         let dev = SimDev::new(&ctx)?;
         let bank = SimBank::new(&dev)?;
@@ -261,46 +319,84 @@ impl Sim {
             dev.enable()?;
         }
 
-        Ok(Self { ctx, dev, bank })
+        Ok(Self {
+            _ctx: ctx,
+            dev,
+            bank,
+            banks: Vec::new(),
+        })
+    }
+
+    /// Add another bank to the device, with an optional number of lines and
+    /// label.
+    ///
+    /// The bank lives as long as the `Sim`, the returned handle can be used to
+    /// query its GPIO chip once the device is enabled.
+    pub fn add_bank(&mut self, ngpio: Option<u64>, label: Option<&str>) -> Result<SimBank> {
+        let bank = SimBank::new(&self.dev)?;
+
+        if let Some(ngpio) = ngpio {
+            bank.set_num_lines(ngpio)?;
+        }
+
+        if let Some(label) = label {
+            bank.set_label(label)?;
+        }
+
+        self.banks.push(bank.clone());
+        Ok(bank)
     }
 
-    pub(crate) fn chip_name(&self) -> &str {
-        self.bank.chip_name().unwrap()
+    /// Get the name of the GPIO chip, available once the device is enabled.
+    pub fn chip_name(&self) -> Result<&str> {
+        self.bank.chip_name()
     }
 
-    pub fn dev_path(&self) -> &str {
-        self.bank.dev_path().unwrap()
+    /// Get the path to the GPIO chip's character device, available once the
+    /// device is enabled.
+    pub fn dev_path(&self) -> Result<&str> {
+        self.bank.dev_path()
     }
 
-    pub(crate) fn val(&self, offset: u32) -> Result<u32> {
-        self.bank.val(offset)
+    /// Get the value of the line as driven by its user, `GPIOSIM_VALUE_*`.
+    pub fn get_value(&self, offset: u32) -> Result<u32> {
+        self.bank.get_value(offset)
     }
 
-    pub(crate) fn set_label(&self, label: &str) -> Result<()> {
+    /// Set the label of the chip.
+    pub fn set_label(&self, label: &str) -> Result<()> {
         self.bank.set_label(label)
     }
 
-    pub(crate) fn set_num_lines(&self, num: u64) -> Result<()> {
+    /// Set the number of lines exposed by the chip.
+    pub fn set_num_lines(&self, num: u64) -> Result<()> {
         self.bank.set_num_lines(num)
     }
 
-    pub(crate) fn set_line_name(&self, offset: u32, name: &str) -> Result<()> {
+    /// Set the name of a line.
+    pub fn set_line_name(&self, offset: u32, name: &str) -> Result<()> {
         self.bank.set_line_name(offset, name)
     }
 
-    pub(crate) fn set_pull(&self, offset: u32, pull: i32) -> Result<()> {
+    /// Pull the line up or down, `GPIOSIM_PULL_*`, simulating an externally
+    /// driven input.
+    pub fn set_pull(&self, offset: u32, pull: i32) -> Result<()> {
         self.bank.set_pull(offset, pull)
     }
 
-    pub(crate) fn hog_line(&self, offset: u32, name: &str, dir: i32) -> Result<()> {
+    /// Hog the line with the given consumer name and direction,
+    /// `GPIOSIM_HOG_DIR_*`.
+    pub fn hog_line(&self, offset: u32, name: &str, dir: i32) -> Result<()> {
         self.bank.hog_line(offset, name, dir)
     }
 
-    pub(crate) fn enable(&self) -> Result<()> {
+    /// Enable the device, creating the GPIO chip.
+    pub fn enable(&self) -> Result<()> {
         self.dev.enable()
     }
 
-    pub(crate) fn disable(&self) -> Result<()> {
+    /// Disable the device, removing the GPIO chip.
+    pub fn disable(&self) -> Result<()> {
         self.dev.disable()
     }
 }
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+use libc::EFD_NONBLOCK;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+use vmm_sys_util::errno::Error as IoError;
+use vmm_sys_util::eventfd::EventFd;
+
+use super::{Error, Result};
+
+/// Wait canceller
+///
+/// Allows waking up threads blocked in
+/// [`LineRequest::wait_edge_event_cancellable`](crate::LineRequest::wait_edge_event_cancellable)
+/// from another thread. It is backed by an eventfd, once cancelled it stays
+/// signalled (and every wait using it fails with `Error::Cancelled`) until
+/// reset.
+#[derive(Debug)]
+pub struct WaitCanceller {
+    event: EventFd,
+}
+
+impl WaitCanceller {
+    /// Create a new wait canceller.
+    pub fn new() -> Result<Self> {
+        let event = EventFd::new(EFD_NONBLOCK)
+            .map_err(|_| Error::OperationFailed("Gpio WaitCanceller new", IoError::last()))?;
+
+        Ok(Self { event })
+    }
+
+    /// Cancel all waits, current and future, using this canceller.
+    pub fn cancel(&self) -> Result<()> {
+        self.event
+            .write(1)
+            .map_err(|_| Error::OperationFailed("Gpio WaitCanceller cancel", IoError::last()))
+    }
+
+    /// Clear a previous cancellation, making the canceller usable again.
+    pub fn reset(&self) {
+        // Fails with EAGAIN if the canceller isn't signalled, nothing to do then.
+        let _ = self.event.read();
+    }
+}
+
+impl AsRawFd for WaitCanceller {
+    /// Get the file descriptor polled for cancellation.
+    fn as_raw_fd(&self) -> RawFd {
+        self.event.as_raw_fd()
+    }
+}
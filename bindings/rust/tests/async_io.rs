@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+#![cfg(feature = "async-io")]
+
+mod common;
+
+mod async_io {
+    use std::sync::Arc;
+    use std::thread::{sleep, spawn};
+    use std::time::Duration;
+
+    use smol::stream::StreamExt;
+
+    use crate::common::*;
+    use libgpiod::async_io::EdgeEventStream;
+    use libgpiod::{Chip, Edge, LineConfig, LineEdgeEvent, RequestConfig};
+
+    const NGPIO: u64 = 8;
+    const GPIO: u32 = 3;
+
+    #[test]
+    fn edge_events() {
+        let sim = Arc::new(Sim::new(Some(NGPIO), None, true).unwrap());
+        let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+        let rconfig = RequestConfig::new().unwrap();
+        rconfig.set_offsets(&[GPIO]);
+        let mut lconfig = LineConfig::new().unwrap();
+        lconfig.set_edge_detection_default(Edge::Both);
+
+        let request = chip.request_lines(&rconfig, &lconfig).unwrap();
+        let mut stream = EdgeEventStream::new(request).unwrap();
+
+        // Generate events
+        let thread_sim = sim.clone();
+        spawn(move || {
+            sleep(Duration::from_millis(30));
+            thread_sim.set_pull(GPIO, GPIOSIM_PULL_UP as i32).unwrap();
+        });
+
+        let event = smol::block_on(stream.next()).unwrap().unwrap();
+        assert_eq!(event.get_event_type().unwrap(), LineEdgeEvent::Rising);
+        assert_eq!(event.get_line_offset(), GPIO);
+    }
+}
@@ -6,12 +6,16 @@
 mod common;
 
 mod chip {
-    use libc::{ENODEV, ENOENT, ENOTTY};
+    use libc::{EINVAL, ENODEV, ENOENT, ENOTTY};
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+    use std::hash::{Hash, Hasher};
+    use std::path::Path;
 
     use vmm_sys_util::errno::Error as IoError;
 
     use crate::common::*;
-    use libgpiod::{Chip, Error as ChipError};
+    use libgpiod::{count_gpiochips, Chip, Edge, Error as ChipError, LineConfig, RequestConfig};
 
     mod create {
         use super::*;
@@ -40,10 +44,118 @@ mod chip {
             );
         }
 
+        #[test]
+        fn checked_failure() {
+            assert_eq!(
+                Chip::open_checked("/tmp").unwrap_err(),
+                ChipError::NotAGpioChip
+            );
+            assert_eq!(
+                Chip::open_checked("/dev/null").unwrap_err(),
+                ChipError::NotAGpioChip
+            );
+
+            // Missing files are still reported by errno
+            assert_eq!(
+                Chip::open_checked("/dev/nonexistent").unwrap_err(),
+                ChipError::OperationFailed("Gpio Chip open", IoError::new(ENOENT))
+            );
+        }
+
+        #[test]
+        fn existing_checked() {
+            let sim = Sim::new(None, None, true).unwrap();
+            Chip::open_checked(sim.dev_path().unwrap()).unwrap();
+        }
+
+        #[test]
+        fn close() {
+            let sim = Sim::new(None, None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            chip.close();
+
+            // Reopening the same path still works
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            assert_eq!(chip.get_path().unwrap(), sim.dev_path().unwrap());
+        }
+
         #[test]
         fn existing() {
             let sim = Sim::new(None, None, true).unwrap();
-            Chip::open(sim.dev_path()).unwrap();
+            Chip::open(sim.dev_path().unwrap()).unwrap();
+        }
+    }
+
+    mod find {
+        use super::*;
+
+        #[test]
+        fn by_label_pattern() {
+            let sim_left = Sim::new(None, Some("pattern-test-left"), true).unwrap();
+            let sim_right = Sim::new(None, Some("pattern-test-right"), true).unwrap();
+
+            let chips = Chip::find_by_label_pattern("test-left").unwrap();
+            let paths: Vec<&str> = chips.iter().map(|chip| chip.get_path().unwrap()).collect();
+            assert!(paths.contains(&sim_left.dev_path().unwrap()));
+            assert!(!paths.contains(&sim_right.dev_path().unwrap()));
+
+            let chips = Chip::find_by_label_pattern("pattern-test-").unwrap();
+            assert_eq!(chips.len(), 2);
+
+            assert!(Chip::find_by_label_pattern("pattern-test-none")
+                .unwrap()
+                .is_empty());
+        }
+
+        #[test]
+        fn count() {
+            let _sim = Sim::new(None, None, true).unwrap();
+
+            assert!(count_gpiochips().unwrap() >= 1);
+        }
+    }
+
+    mod remove {
+        use super::*;
+
+        #[test]
+        fn line_info_after_removal() {
+            let sim = Sim::new(Some(8), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            chip.line_info(0).unwrap();
+
+            sim.disable().unwrap();
+            assert_eq!(
+                chip.line_info(0).unwrap_err(),
+                ChipError::ChipRemoved("Gpio LineInfo line-info")
+            );
+        }
+    }
+
+    mod reopen {
+        use super::*;
+
+        #[test]
+        fn after_removal() {
+            let sim = Sim::new(Some(8), None, true).unwrap();
+            let mut chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            sim.disable().unwrap();
+            assert_eq!(
+                chip.line_info(0).unwrap_err(),
+                ChipError::ChipRemoved("Gpio LineInfo line-info")
+            );
+
+            // Device is still absent
+            assert_eq!(
+                chip.reopen().unwrap_err(),
+                ChipError::OperationFailed("Gpio Chip open", IoError::new(ENOENT))
+            );
+
+            sim.enable().unwrap();
+            chip.reopen().unwrap();
+            assert_eq!(chip.line_info(0).unwrap().get_offset(), 0);
         }
     }
 
@@ -55,15 +167,77 @@ mod chip {
         #[test]
         fn verify() {
             let sim = Sim::new(Some(NGPIO), Some(LABEL), true).unwrap();
-            let chip = Chip::open(sim.dev_path()).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
 
             assert_eq!(chip.get_label().unwrap(), LABEL);
-            assert_eq!(chip.get_name().unwrap(), sim.chip_name());
-            assert_eq!(chip.get_path().unwrap(), sim.dev_path());
+            assert_eq!(chip.get_name().unwrap(), sim.chip_name().unwrap());
+            assert_eq!(chip.get_path().unwrap(), sim.dev_path().unwrap());
             assert_eq!(chip.get_num_lines(), NGPIO as u32);
             chip.get_fd().unwrap();
         }
 
+        #[test]
+        fn display() {
+            let sim = Sim::new(Some(NGPIO), Some(LABEL), true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            assert_eq!(
+                format!("{}", chip),
+                format!("{} [{}] ({} lines)", sim.chip_name().unwrap(), LABEL, NGPIO)
+            );
+        }
+
+        #[test]
+        fn cached_num_lines() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            // Served from the cached chip information on every call
+            for _ in 0..100 {
+                assert_eq!(chip.get_num_lines(), NGPIO as u32);
+            }
+
+            // Refreshing the information doesn't affect the cached copy
+            chip.info().unwrap();
+            assert_eq!(chip.get_num_lines(), NGPIO as u32);
+        }
+
+        #[test]
+        fn path_buf() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            assert_eq!(
+                chip.get_path_buf().unwrap(),
+                Path::new(sim.dev_path().unwrap())
+            );
+        }
+
+        #[test]
+        fn compare() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let sim2 = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let chip_dup = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let chip2 = Chip::open(sim2.dev_path().unwrap()).unwrap();
+
+            assert_eq!(chip, chip_dup);
+            assert_ne!(chip, chip2);
+
+            let hash = |chip: &Chip| {
+                let mut hasher = DefaultHasher::new();
+                chip.hash(&mut hasher);
+                hasher.finish()
+            };
+            assert_eq!(hash(&chip), hash(&chip_dup));
+
+            let mut chips = HashSet::new();
+            chips.insert(chip);
+            chips.insert(chip_dup);
+            chips.insert(chip2);
+            assert_eq!(chips.len(), 2);
+        }
+
         #[test]
         fn line_lookup() {
             let sim = Sim::new(Some(NGPIO), None, false).unwrap();
@@ -75,7 +249,7 @@ mod chip {
             sim.set_line_name(11, "ten").unwrap();
             sim.enable().unwrap();
 
-            let chip = Chip::open(sim.dev_path()).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
 
             // Success case
             assert_eq!(chip.find_line("zero").unwrap(), 0);
@@ -92,5 +266,128 @@ mod chip {
                 ChipError::OperationFailed("Gpio Chip find-line", IoError::new(ENOENT))
             );
         }
+
+        #[test]
+        fn line_lookup_all() {
+            let sim = Sim::new(Some(NGPIO), None, false).unwrap();
+            sim.set_line_name(0, "zero").unwrap();
+            sim.set_line_name(10, "ten").unwrap();
+            sim.set_line_name(11, "ten").unwrap();
+            sim.enable().unwrap();
+
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            assert_eq!(chip.find_all_lines("zero").unwrap(), [0]);
+            assert_eq!(chip.find_all_lines("ten").unwrap(), [10, 11]);
+            assert!(chip.find_all_lines("nonexistent").unwrap().is_empty());
+        }
+
+        #[test]
+        fn line_info_by_name() {
+            let sim = Sim::new(Some(NGPIO), None, false).unwrap();
+            sim.set_line_name(3, "three").unwrap();
+            sim.enable().unwrap();
+
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            let info = chip.get_line_info_by_name("three").unwrap();
+            assert_eq!(info.get_offset(), 3);
+            assert_eq!(info.get_name().unwrap(), "three");
+
+            // Failure
+            assert_eq!(
+                chip.get_line_info_by_name("nonexistent").unwrap_err(),
+                ChipError::NameNotFound("GPIO line")
+            );
+        }
+
+        #[test]
+        fn line_used() {
+            let sim = Sim::new(Some(NGPIO), None, false).unwrap();
+            sim.hog_line(2, "hog", GPIOSIM_HOG_DIR_OUTPUT_HIGH as i32)
+                .unwrap();
+            sim.enable().unwrap();
+
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            assert!(chip.is_line_used(2).unwrap());
+            assert!(!chip.is_line_used(3).unwrap());
+            assert!(chip.is_line_used(NGPIO as u32).is_err());
+        }
+
+        #[test]
+        fn lines_by_consumer() {
+            let sim = Sim::new(Some(NGPIO), None, false).unwrap();
+            sim.hog_line(1, "shared", GPIOSIM_HOG_DIR_OUTPUT_HIGH as i32)
+                .unwrap();
+            sim.hog_line(4, "shared", GPIOSIM_HOG_DIR_INPUT as i32)
+                .unwrap();
+            sim.hog_line(6, "other", GPIOSIM_HOG_DIR_INPUT as i32)
+                .unwrap();
+            sim.enable().unwrap();
+
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            assert_eq!(chip.lines_by_consumer("shared").unwrap(), [1, 4]);
+            assert_eq!(chip.lines_by_consumer("other").unwrap(), [6]);
+            assert!(chip.lines_by_consumer("nonexistent").unwrap().is_empty());
+        }
+
+        #[test]
+        fn edge_detection_lines() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            assert!(chip.lines_with_edge_detection().unwrap().is_empty());
+
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[1, 4, 6]);
+            let mut lconfig = LineConfig::new().unwrap();
+            lconfig.set_edge_detection_override(Edge::Rising, 4);
+            lconfig.set_edge_detection_override(Edge::Both, 6);
+            let _request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+            assert_eq!(
+                chip.lines_with_edge_detection().unwrap(),
+                [(4, Edge::Rising), (6, Edge::Both)]
+            );
+        }
+
+        #[test]
+        fn used_lines() {
+            let sim = Sim::new(Some(NGPIO), None, false).unwrap();
+            sim.hog_line(2, "hog2", GPIOSIM_HOG_DIR_OUTPUT_HIGH as i32)
+                .unwrap();
+            sim.hog_line(5, "hog5", GPIOSIM_HOG_DIR_INPUT as i32)
+                .unwrap();
+            sim.enable().unwrap();
+
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            assert_eq!(chip.used_lines().unwrap(), [2, 5]);
+
+            let free = chip.free_lines().unwrap();
+            assert_eq!(free.len(), NGPIO as usize - 2);
+            assert!(!free.contains(&2));
+            assert!(!free.contains(&5));
+        }
+
+        #[test]
+        fn line_infos_subset() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            let offsets: Vec<u32> = chip
+                .line_infos_for(&[0, 3, 5])
+                .unwrap()
+                .iter()
+                .map(|info| info.get_offset())
+                .collect();
+            assert_eq!(offsets, [0, 3, 5]);
+
+            // Failure
+            assert_eq!(
+                chip.line_infos_for(&[0, NGPIO as u32]).unwrap_err(),
+                ChipError::OperationFailed("Gpio LineInfo line-info", IoError::new(EINVAL))
+            );
+        }
     }
 }
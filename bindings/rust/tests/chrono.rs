@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+#![cfg(feature = "chrono")]
+
+mod common;
+
+mod chrono {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use crate::common::*;
+    use libgpiod::{Chip, Edge, EdgeEventBuffer, EventClock, LineConfig, RequestConfig};
+
+    const NGPIO: u64 = 8;
+    const GPIO: u32 = 3;
+
+    /// Private helper, Returns the current time in seconds since the epoch.
+    fn now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+    }
+
+    #[test]
+    fn edge_event_realtime() {
+        let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+        let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+        let rconfig = RequestConfig::new().unwrap();
+        rconfig.set_offsets(&[GPIO]);
+        let mut lconfig = LineConfig::new().unwrap();
+        lconfig.set_edge_detection_default(Edge::Both);
+        lconfig.set_event_clock_default(EventClock::Realtime);
+
+        let request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+        // Generate event
+        sim.set_pull(GPIO, GPIOSIM_PULL_UP as i32).unwrap();
+        request.wait_edge_event(Duration::from_secs(1)).unwrap();
+
+        let buffer = EdgeEventBuffer::new(1).unwrap();
+        assert_eq!(request.read_edge_event(&buffer, 1).unwrap(), 1);
+        let event = buffer.get_event(0).unwrap();
+
+        let datetime = event.timestamp_datetime(EventClock::Realtime).unwrap();
+        assert!((now() - datetime.timestamp()).abs() < 10);
+
+        // Monotonic timestamps aren't converted
+        assert!(event.timestamp_datetime(EventClock::Monotonic).is_none());
+    }
+
+    #[test]
+    fn info_event() {
+        let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+        let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+        let _info = chip.watch_line_info(GPIO).unwrap();
+
+        // Generate event
+        let rconfig = RequestConfig::new().unwrap();
+        rconfig.set_offsets(&[GPIO]);
+        let lconfig = LineConfig::new().unwrap();
+        let _request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+        chip.wait_info_event(Duration::from_secs(1)).unwrap();
+        let event = chip.read_info_event().unwrap();
+
+        let datetime = event.timestamp_datetime().unwrap();
+        assert!((now() - datetime.timestamp()).abs() < 10);
+    }
+}
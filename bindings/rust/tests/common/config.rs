@@ -87,7 +87,7 @@ impl TestConfig {
     }
 
     pub(crate) fn request_lines(&mut self) -> Result<()> {
-        let chip = Chip::open(self.sim.dev_path())?;
+        let chip = Chip::open(self.sim.dev_path().unwrap())?;
 
         self.request = Some(chip.request_lines(&self.rconfig, &self.lconfig)?);
         self.chip = Some(chip);
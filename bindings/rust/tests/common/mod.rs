@@ -3,11 +3,8 @@
 // Copyright 2022 Linaro Ltd. All Rights Reserved.
 //     Viresh Kumar <viresh.kumar@linaro.org>
 
-#[allow(dead_code)]
-mod sim;
-
 #[allow(unused_imports)]
-pub(crate) use sim::*;
+pub(crate) use libgpiod::sim::*;
 
 #[allow(dead_code)]
 mod config;
@@ -6,7 +6,7 @@
 mod common;
 
 mod edge_event {
-    use libc::EINVAL;
+    use libc::{EINVAL, ENOBUFS};
     use std::sync::Arc;
     use std::thread::{sleep, spawn};
     use std::time::Duration;
@@ -14,7 +14,10 @@ mod edge_event {
     use vmm_sys_util::errno::Error as IoError;
 
     use crate::common::*;
-    use libgpiod::{Direction, Edge, EdgeEventBuffer, Error as ChipError, LineEdgeEvent};
+    use libgpiod::{
+        Chip, Direction, Edge, EdgeEventBuffer, Error as ChipError, LineConfig, LineEdgeEvent,
+        RequestConfig, WaitCanceller, MAX_EDGE_EVENT_BUFFER_CAPACITY,
+    };
     use libgpiod_sys::{GPIOSIM_PULL_DOWN, GPIOSIM_PULL_UP};
 
     const NGPIO: u64 = 8;
@@ -36,6 +39,30 @@ mod edge_event {
         fn max_capacity() {
             assert_eq!(EdgeEventBuffer::new(1024 * 2).unwrap().get_capacity(), 1024);
         }
+
+        #[test]
+        fn checked_capacity() {
+            assert_eq!(
+                EdgeEventBuffer::try_new(MAX_EDGE_EVENT_BUFFER_CAPACITY)
+                    .unwrap()
+                    .get_capacity(),
+                MAX_EDGE_EVENT_BUFFER_CAPACITY
+            );
+            assert_eq!(
+                EdgeEventBuffer::try_new(2048).err(),
+                Some(ChipError::InvalidValue("capacity", 2048))
+            );
+        }
+
+        #[test]
+        fn resize() {
+            let mut buffer = EdgeEventBuffer::new(16).unwrap();
+            assert_eq!(buffer.get_capacity(), 16);
+
+            buffer.resize(256).unwrap();
+            assert_eq!(buffer.get_capacity(), 256);
+            assert_eq!(buffer.get_num_events(), 0);
+        }
     }
 
     mod failure {
@@ -58,6 +85,81 @@ mod edge_event {
             );
         }
 
+        #[test]
+        fn poll_timeout() {
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[0]));
+            config.lconfig_edge(Some(Edge::Both));
+            config.request_lines().unwrap();
+
+            // No events available
+            assert!(!config
+                .request()
+                .poll_edge_event(Duration::from_millis(100))
+                .unwrap());
+        }
+
+        #[test]
+        fn wait_cancelled() {
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[0]));
+            config.lconfig_edge(Some(Edge::Both));
+            config.request_lines().unwrap();
+
+            let canceller = Arc::new(WaitCanceller::new().unwrap());
+            let thread_canceller = canceller.clone();
+
+            // Cancel the blocked wait from another thread
+            let handle = spawn(move || {
+                sleep(Duration::from_millis(30));
+                thread_canceller.cancel().unwrap();
+            });
+
+            assert_eq!(
+                config
+                    .request()
+                    .wait_edge_event_cancellable(None, &canceller)
+                    .unwrap_err(),
+                ChipError::Cancelled
+            );
+            handle.join().unwrap();
+
+            // Stays cancelled until reset
+            assert_eq!(
+                config
+                    .request()
+                    .wait_edge_event_cancellable(None, &canceller)
+                    .unwrap_err(),
+                ChipError::Cancelled
+            );
+
+            canceller.reset();
+            assert_eq!(
+                config
+                    .request()
+                    .wait_edge_event_cancellable(Some(Duration::from_millis(100)), &canceller)
+                    .unwrap_err(),
+                ChipError::OperationTimedOut
+            );
+        }
+
+        #[test]
+        fn read_timeout() {
+            let buf = EdgeEventBuffer::new(0).unwrap();
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[0]));
+            config.lconfig_edge(Some(Edge::Both));
+            config.request_lines().unwrap();
+
+            assert_eq!(
+                config
+                    .request()
+                    .read_edge_event_timeout(&buf, buf.get_capacity(), Duration::from_millis(100))
+                    .unwrap(),
+                0
+            );
+        }
+
         #[test]
         fn dir_out_edge_failure() {
             let mut config = TestConfig::new(NGPIO).unwrap();
@@ -316,6 +418,347 @@ mod edge_event {
             );
         }
 
+        #[test]
+        fn disable_edge_on_one_line() {
+            const GPIO: [u32; 2] = [0, 1];
+            let buf = EdgeEventBuffer::new(0).unwrap();
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&GPIO));
+            config.lconfig_edge(Some(Edge::Rising));
+            config.request_lines().unwrap();
+
+            config
+                .request()
+                .set_edge_detection(&[GPIO[0]], Edge::None)
+                .unwrap();
+
+            // Generate events on both lines
+            trigger_rising_edge_events_on_two_offsets(config.sim(), GPIO);
+
+            config
+                .request()
+                .wait_edge_event(Duration::from_secs(1))
+                .unwrap();
+
+            assert_eq!(
+                config
+                    .request()
+                    .read_edge_event(&buf, buf.get_capacity())
+                    .unwrap(),
+                1
+            );
+
+            let event = buf.get_event(0).unwrap();
+            assert_eq!(event.get_event_type().unwrap(), LineEdgeEvent::Rising);
+            assert_eq!(event.get_line_offset(), GPIO[1]);
+
+            // No events from the disabled line
+            assert_eq!(
+                config
+                    .request()
+                    .wait_edge_event(Duration::from_millis(100))
+                    .unwrap_err(),
+                ChipError::OperationTimedOut
+            );
+        }
+
+        #[test]
+        fn read_until() {
+            const GPIO: u32 = 2;
+            let buf = EdgeEventBuffer::new(1).unwrap();
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[GPIO]));
+            config.lconfig_edge(Some(Edge::Both));
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            // Generate events
+            trigger_falling_and_rising_edge(config.sim(), GPIO);
+
+            // Events accumulate across multiple reads of the small buffer
+            let events = request
+                .read_edge_events_until(&buf, 2, Duration::from_secs(1))
+                .unwrap();
+            assert_eq!(events.len(), 2);
+            assert_eq!(events[0].get_event_type().unwrap(), LineEdgeEvent::Rising);
+            assert_eq!(events[1].get_event_type().unwrap(), LineEdgeEvent::Falling);
+
+            // Timeout expires with no more events
+            let events = request
+                .read_edge_events_until(&buf, 2, Duration::from_millis(100))
+                .unwrap();
+            assert!(events.is_empty());
+        }
+
+        #[test]
+        fn wait_for_edge() {
+            const GPIO: u32 = 2;
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.set_pull(&[GPIO], &[GPIOSIM_PULL_UP]);
+            config.rconfig(Some(&[GPIO]));
+            config.lconfig_edge(Some(Edge::Both));
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            // Generate events
+            let sim = config.sim();
+            spawn(move || {
+                sleep(Duration::from_millis(30));
+                sim.set_pull(GPIO, GPIOSIM_PULL_DOWN as i32).unwrap();
+
+                sleep(Duration::from_millis(30));
+                sim.set_pull(GPIO, GPIOSIM_PULL_UP as i32).unwrap();
+            });
+
+            // The falling edge is discarded
+            let event = request
+                .wait_for_edge(GPIO, LineEdgeEvent::Rising, Duration::from_secs(1))
+                .unwrap();
+            assert_eq!(event.get_event_type().unwrap(), LineEdgeEvent::Rising);
+            assert_eq!(event.get_line_offset(), GPIO);
+            assert!(!request.poll_edge_event(Duration::from_millis(100)).unwrap());
+
+            assert_eq!(
+                request
+                    .wait_for_edge(GPIO, LineEdgeEvent::Rising, Duration::from_millis(100))
+                    .err(),
+                Some(ChipError::OperationTimedOut)
+            );
+        }
+
+        #[test]
+        fn timestamp_ns() {
+            const GPIO: u32 = 1;
+            let buf = EdgeEventBuffer::new(0).unwrap();
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[GPIO]));
+            config.lconfig_edge(Some(Edge::Both));
+            config.request_lines().unwrap();
+
+            // Generate events
+            trigger_multiple_events(config.sim(), GPIO);
+
+            let count = config
+                .request()
+                .read_edge_event(&buf, buf.get_capacity())
+                .unwrap();
+            assert_eq!(count, 3);
+
+            for i in 0..count {
+                let event = buf.get_event(i as u64).unwrap();
+                let ns = event.get_timestamp_ns();
+                let ts = event.get_timestamp();
+
+                assert_eq!(ns / 1_000_000_000, ts.as_secs());
+                assert_eq!((ns % 1_000_000_000) as u32, ts.subsec_nanos());
+            }
+        }
+
+        #[test]
+        fn next_events() {
+            const GPIO: u32 = 3;
+            let buf = EdgeEventBuffer::new(0).unwrap();
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[GPIO]));
+            config.lconfig_edge(Some(Edge::Rising));
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            // Timeout
+            assert_eq!(
+                request
+                    .next_edge_events(Duration::from_millis(10), &buf, buf.get_capacity())
+                    .unwrap(),
+                0
+            );
+
+            config.sim().set_pull(GPIO, GPIOSIM_PULL_UP as i32).unwrap();
+
+            assert_eq!(
+                request
+                    .next_edge_events(Duration::from_secs(1), &buf, buf.get_capacity())
+                    .unwrap(),
+                1
+            );
+            assert_eq!(buf.get_event(0).unwrap().get_line_offset(), GPIO);
+        }
+
+        #[test]
+        fn buffered_events() {
+            const GPIO: u32 = 4;
+            let sim = Arc::new(Sim::new(Some(NGPIO), None, true).unwrap());
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[GPIO]);
+            let mut lconfig = LineConfig::new().unwrap();
+            lconfig.set_edge_detection_default(Edge::Both);
+
+            let request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+            // No buffer configured
+            assert_eq!(
+                request.read_buffered_edge_events(|_| ()).unwrap_err(),
+                ChipError::OperationFailed(
+                    "Gpio LineRequest buffered-edge-event-read",
+                    IoError::new(ENOBUFS)
+                )
+            );
+
+            let request = request.with_event_buffer(4).unwrap();
+            let mut offsets = Vec::new();
+
+            trigger_multiple_events(sim.clone(), GPIO);
+            assert_eq!(
+                request
+                    .read_buffered_edge_events(|event| offsets.push(event.get_line_offset()))
+                    .unwrap(),
+                3
+            );
+
+            sim.set_pull(GPIO, GPIOSIM_PULL_DOWN as i32).unwrap();
+            assert_eq!(
+                request
+                    .read_buffered_edge_events(|event| offsets.push(event.get_line_offset()))
+                    .unwrap(),
+                1
+            );
+            assert_eq!(offsets, [GPIO; 4]);
+        }
+
+        #[test]
+        fn owned_events() {
+            const GPIO: u32 = 5;
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[GPIO]));
+            config.lconfig_edge(Some(Edge::Both));
+            config.request_lines().unwrap();
+
+            // Generate events
+            trigger_multiple_events(config.sim(), GPIO);
+
+            let events = config.request().read_edge_events_owned(2).unwrap();
+            assert_eq!(events.len(), 2);
+
+            // Inspect the events from another thread
+            let types = spawn(move || {
+                events
+                    .iter()
+                    .map(|event| {
+                        assert_eq!(event.get_line_offset(), GPIO);
+                        event.get_event_type()
+                    })
+                    .collect::<Vec<LineEdgeEvent>>()
+            })
+            .join()
+            .unwrap();
+            assert_eq!(types, [LineEdgeEvent::Rising, LineEdgeEvent::Falling]);
+
+            // The remaining event
+            let events = config.request().read_edge_events_owned(8).unwrap();
+            assert_eq!(events.len(), 1);
+            assert!(config
+                .request()
+                .read_edge_events_owned(0)
+                .unwrap()
+                .is_empty());
+        }
+
+        #[test]
+        fn events_read_counter() {
+            const GPIO: u32 = 1;
+            let buf = EdgeEventBuffer::new(2).unwrap();
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[GPIO]));
+            config.lconfig_edge(Some(Edge::Both));
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            assert_eq!(request.total_events_read(), 0);
+
+            // Generate events
+            trigger_multiple_events(config.sim(), GPIO);
+
+            assert_eq!(request.read_edge_event(&buf, 2).unwrap(), 2);
+            assert_eq!(request.total_events_read(), 2);
+            assert_eq!(request.read_edge_event(&buf, 2).unwrap(), 1);
+            assert_eq!(request.total_events_read(), 3);
+        }
+
+        #[test]
+        fn compare_events() {
+            const GPIO: u32 = 1;
+            let buf = EdgeEventBuffer::new(0).unwrap();
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[GPIO]));
+            config.lconfig_edge(Some(Edge::Both));
+            config.request_lines().unwrap();
+
+            // Generate events
+            trigger_multiple_events(config.sim(), GPIO);
+
+            config
+                .request()
+                .wait_edge_event(Duration::from_secs(1))
+                .unwrap();
+            assert_eq!(
+                config
+                    .request()
+                    .read_edge_event(&buf, buf.get_capacity())
+                    .unwrap(),
+                3
+            );
+
+            // A copy equals its source, other events don't
+            let event = buf.get_event(0).unwrap();
+            assert!(event == buf.get_event_copy(0).unwrap());
+            assert!(event != buf.get_event(1).unwrap());
+            assert!(event != buf.get_event_copy(2).unwrap());
+        }
+
+        #[test]
+        fn drain_queued_events() {
+            const GPIO: u32 = 1;
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[GPIO]));
+            config.lconfig_edge(Some(Edge::Both));
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            assert_eq!(request.drain_edge_events().unwrap(), 0);
+
+            // Generate events
+            trigger_multiple_events(config.sim(), GPIO);
+
+            assert_eq!(request.drain_edge_events().unwrap(), 3);
+            assert!(!request.poll_edge_event(Duration::from_millis(100)).unwrap());
+        }
+
+        #[test]
+        fn pending_events() {
+            const GPIO: u32 = 1;
+            let buf = EdgeEventBuffer::new(0).unwrap();
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[GPIO]));
+            config.lconfig_edge(Some(Edge::Both));
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            assert!(!request.has_pending_edge_events().unwrap());
+
+            // Generate three events
+            trigger_multiple_events(config.sim(), GPIO);
+
+            // Peeking doesn't consume the events
+            assert!(request.has_pending_edge_events().unwrap());
+            assert!(request.has_pending_edge_events().unwrap());
+            assert_eq!(
+                request.read_edge_event(&buf, buf.get_capacity()).unwrap(),
+                3
+            );
+
+            assert!(!request.has_pending_edge_events().unwrap());
+        }
+
         #[test]
         fn multiple_events() {
             const GPIO: u32 = 1;
@@ -358,6 +801,77 @@ mod edge_event {
             }
         }
 
+        #[test]
+        fn drain_events() {
+            const GPIO: u32 = 1;
+            let buf = EdgeEventBuffer::new(0).unwrap();
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[GPIO]));
+            config.lconfig_edge(Some(Edge::Both));
+            config.request_lines().unwrap();
+
+            // Generate events
+            trigger_multiple_events(config.sim(), GPIO);
+
+            // Read multiple events
+            config
+                .request()
+                .wait_edge_event(Duration::from_secs(1))
+                .unwrap();
+
+            assert_eq!(
+                config
+                    .request()
+                    .read_edge_event(&buf, buf.get_capacity())
+                    .unwrap(),
+                3
+            );
+
+            let seqnos: Vec<u64> = buf
+                .drain()
+                .map(|event| event.unwrap().get_global_seqno())
+                .collect();
+            assert_eq!(seqnos, [1, 2, 3]);
+
+            // Events stay in the buffer until the next read
+            assert_eq!(buf.get_num_events(), 3);
+            assert_eq!(buf.drain().count(), 3);
+        }
+
+        #[test]
+        fn monitor() {
+            const GPIO: u32 = 2;
+            let sim = Arc::new(Sim::new(Some(NGPIO), None, true).unwrap());
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[GPIO]);
+            let mut lconfig = LineConfig::new().unwrap();
+            lconfig.set_edge_detection_default(Edge::Both);
+
+            let request = Arc::new(chip.request_lines(&rconfig, &lconfig).unwrap());
+            let monitor = request.spawn_monitor();
+
+            // Generate events
+            trigger_falling_and_rising_edge(sim.clone(), GPIO);
+
+            let event = monitor
+                .receiver()
+                .recv_timeout(Duration::from_secs(1))
+                .unwrap()
+                .unwrap();
+            assert_eq!(event.get_event_type(), LineEdgeEvent::Rising);
+            assert_eq!(event.get_line_offset(), GPIO);
+
+            let event = monitor
+                .receiver()
+                .recv_timeout(Duration::from_secs(1))
+                .unwrap()
+                .unwrap();
+            assert_eq!(event.get_event_type(), LineEdgeEvent::Falling);
+            assert_eq!(event.get_line_offset(), GPIO);
+        }
+
         #[test]
         fn over_capacity() {
             const GPIO: u32 = 2;
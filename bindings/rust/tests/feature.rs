@@ -0,0 +1,17 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+mod feature {
+    use libgpiod::{feature_supported, Feature};
+
+    #[test]
+    fn supported() {
+        let debounce = feature_supported(Feature::Debounce);
+        let realtime = feature_supported(Feature::RealtimeEventClock);
+
+        // Realtime event clock came after debouncing
+        assert!(!realtime || debounce);
+    }
+}
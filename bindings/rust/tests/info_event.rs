@@ -45,7 +45,7 @@ mod info_event {
         #[test]
         fn failure() {
             let sim = Sim::new(Some(NGPIO), None, true).unwrap();
-            let chip = Chip::open(sim.dev_path()).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
 
             assert_eq!(
                 chip.watch_line_info(NGPIO as u32).unwrap_err(),
@@ -62,10 +62,20 @@ mod info_event {
             );
         }
 
+        #[test]
+        fn poll_timeout() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let _info = chip.watch_line_info(GPIO).unwrap();
+
+            // No events available
+            assert!(!chip.poll_info_event(Duration::from_millis(100)).unwrap());
+        }
+
         #[test]
         fn verify() {
             let sim = Sim::new(Some(NGPIO), None, true).unwrap();
-            let chip = Chip::open(sim.dev_path()).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
             let info = chip.watch_line_info(GPIO).unwrap();
 
             assert_eq!(info.get_offset(), GPIO);
@@ -74,7 +84,7 @@ mod info_event {
         #[test]
         fn reconfigure() {
             let sim = Sim::new(Some(NGPIO), None, true).unwrap();
-            let chip = Arc::new(Chip::open(sim.dev_path()).unwrap());
+            let chip = Arc::new(Chip::open(sim.dev_path().unwrap()).unwrap());
             let info = chip.watch_line_info(GPIO).unwrap();
 
             assert_eq!(info.get_direction().unwrap(), Direction::Input);
@@ -86,6 +96,7 @@ mod info_event {
             chip.wait_info_event(Duration::from_secs(1)).unwrap();
             let event = chip.read_info_event().unwrap();
             let ts_req = event.get_timestamp();
+            assert_eq!(Duration::from_nanos(event.get_timestamp_ns()), ts_req);
 
             assert_eq!(event.get_event_type().unwrap(), Event::LineRequested);
             assert_eq!(
@@ -122,5 +133,100 @@ mod info_event {
             assert!(ts_rel > ts_rec);
             assert!(ts_rec > ts_req);
         }
+
+        #[test]
+        fn late_watch() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Arc::new(Chip::open(sim.dev_path().unwrap()).unwrap());
+            let mut info = chip.line_info(GPIO).unwrap();
+
+            info.watch(&chip).unwrap();
+            assert_eq!(info.get_offset(), GPIO);
+
+            // Generate events
+            request_reconfigure_line(chip.clone());
+
+            chip.wait_info_event(Duration::from_secs(1)).unwrap();
+            let event = chip.read_info_event().unwrap();
+            assert_eq!(event.get_event_type().unwrap(), Event::LineRequested);
+
+            chip.wait_info_event(Duration::from_secs(1)).unwrap();
+            let event = chip.read_info_event().unwrap();
+            assert_eq!(event.get_event_type().unwrap(), Event::LineConfigChanged);
+        }
+
+        #[test]
+        fn debug() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Arc::new(Chip::open(sim.dev_path().unwrap()).unwrap());
+            let _info = chip.watch_line_info(GPIO).unwrap();
+
+            // Generate events
+            request_reconfigure_line(chip.clone());
+
+            chip.wait_info_event(Duration::from_secs(1)).unwrap();
+            let event = chip.read_info_event().unwrap();
+            let debug = format!("{:?}", event);
+
+            assert!(debug.contains("LineRequested"));
+            assert!(debug.contains(&format!("offset: Ok({})", GPIO)));
+        }
+
+        #[test]
+        fn guard() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Arc::new(Chip::open(sim.dev_path().unwrap()).unwrap());
+            let guard = chip.watch_guard(&[3, GPIO]).unwrap();
+
+            assert_eq!(guard.infos().len(), 2);
+            assert_eq!(guard.info(GPIO).unwrap().get_offset(), GPIO);
+            assert!(guard.info(0).is_none());
+
+            // Failure unwatches the lines already watched
+            assert_eq!(
+                chip.watch_guard(&[0, NGPIO as u32]).unwrap_err(),
+                ChipError::OperationFailed("Gpio LineInfo line-info", IoError::new(EINVAL))
+            );
+
+            drop(guard);
+
+            // Generate events
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[0, 3, GPIO]);
+            let lconfig = LineConfig::new().unwrap();
+            let _request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+            // No events available
+            assert!(!chip.poll_info_event(Duration::from_millis(100)).unwrap());
+        }
+
+        #[test]
+        fn owned() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Arc::new(Chip::open(sim.dev_path().unwrap()).unwrap());
+            // The line is unwatched once the info is dropped
+            let _info = chip.watch_line_info(GPIO).unwrap();
+
+            // Generate events
+            request_reconfigure_line(chip.clone());
+
+            // Line requested event
+            chip.wait_info_event(Duration::from_secs(1)).unwrap();
+            let event = chip.read_info_event().unwrap();
+            let ts = event.get_timestamp();
+            let owned = event.to_owned().unwrap();
+            drop(event);
+
+            // Inspect the owned copy from another thread
+            let owned = spawn(move || owned.clone()).join().unwrap();
+
+            assert_eq!(owned.get_event_type(), Event::LineRequested);
+            assert_eq!(owned.get_timestamp(), ts);
+
+            let info = owned.line_info();
+            assert_eq!(info.get_offset(), GPIO);
+            assert!(info.is_used());
+            assert_eq!(info.get_direction(), Direction::Input);
+        }
     }
 }
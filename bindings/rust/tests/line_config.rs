@@ -35,6 +35,135 @@ mod line_config {
         }
     }
 
+    mod output_value {
+        use super::*;
+
+        #[test]
+        fn invalid() {
+            let mut lconfig = LineConfig::new().unwrap();
+            assert_eq!(lconfig.get_output_value_default().unwrap(), 0);
+
+            // libgpiod stores a single bit of the value
+            lconfig.set_output_value_default(2);
+            assert_eq!(lconfig.get_output_value_default().unwrap(), 0);
+            assert_eq!(lconfig.get_output_value_offset(3).unwrap(), 0);
+
+            lconfig.set_output_value_override(2, 3);
+            assert_eq!(lconfig.get_output_value_offset(3).unwrap(), 0);
+        }
+    }
+
+    mod helpers {
+        use super::*;
+
+        #[test]
+        fn input() {
+            let mut lconfig = LineConfig::new().unwrap();
+
+            lconfig.set_edge_detection_default(Edge::Both);
+            lconfig.set_input();
+            assert_eq!(lconfig.get_direction_default().unwrap(), Direction::Input);
+            assert_eq!(lconfig.get_edge_detection_default().unwrap(), Edge::None);
+        }
+
+        #[test]
+        fn output() {
+            let mut lconfig = LineConfig::new().unwrap();
+
+            lconfig.set_output(1);
+            assert_eq!(lconfig.get_direction_default().unwrap(), Direction::Output);
+            assert_eq!(lconfig.get_output_value_default().unwrap(), 1);
+
+            lconfig.set_output(0);
+            assert_eq!(lconfig.get_output_value_default().unwrap(), 0);
+        }
+    }
+
+    mod compare {
+        use super::*;
+
+        fn build(value: u32) -> LineConfig {
+            let mut lconfig = LineConfig::new().unwrap();
+
+            lconfig.set_direction_default(Direction::Output);
+            lconfig.set_drive_override(Drive::OpenDrain, 3);
+            lconfig.set_output_value_override(value, 3);
+            lconfig.set_bias_override(Bias::PullUp, 5);
+            lconfig
+        }
+
+        #[test]
+        fn equal() {
+            assert_eq!(LineConfig::new().unwrap(), LineConfig::new().unwrap());
+            assert_eq!(build(1), build(1));
+
+            // Order in which overrides are applied doesn't matter
+            let mut lconfig = LineConfig::new().unwrap();
+            lconfig.set_bias_override(Bias::PullUp, 5);
+            lconfig.set_output_value_override(1, 3);
+            lconfig.set_drive_override(Drive::OpenDrain, 3);
+            lconfig.set_direction_default(Direction::Output);
+            assert_eq!(lconfig, build(1));
+        }
+
+        #[test]
+        fn not_equal() {
+            assert_ne!(build(0), build(1));
+            assert_ne!(LineConfig::new().unwrap(), build(1));
+
+            let mut lconfig = build(1);
+            lconfig.set_active_low_default(true);
+            assert_ne!(lconfig, build(1));
+        }
+    }
+
+    mod event_clock {
+        use super::*;
+
+        #[test]
+        fn helpers() {
+            let mut lconfig = LineConfig::new().unwrap();
+
+            lconfig.use_realtime_clock();
+            assert_eq!(
+                lconfig.get_event_clock_default().unwrap(),
+                EventClock::Realtime
+            );
+
+            lconfig.use_monotonic_clock();
+            assert_eq!(
+                lconfig.get_event_clock_default().unwrap(),
+                EventClock::Monotonic
+            );
+        }
+    }
+
+    mod merge {
+        use super::*;
+
+        #[test]
+        fn defaults_and_overrides() {
+            let mut lconfig = LineConfig::new().unwrap();
+            lconfig.set_direction_default(Direction::Output);
+            lconfig.set_bias_override(Bias::PullUp, 5);
+            lconfig.set_drive_override(Drive::OpenDrain, 3);
+
+            let mut other = LineConfig::new().unwrap();
+            other.set_active_low_default(true);
+            other.set_drive_override(Drive::OpenSource, 3);
+
+            lconfig.merge(&other).unwrap();
+
+            // Both defaults take effect
+            assert_eq!(lconfig.get_direction_default().unwrap(), Direction::Output);
+            assert!(lconfig.get_active_low_default());
+
+            // Overrides of the other config take precedence
+            assert_eq!(lconfig.get_bias_offset(5).unwrap(), Bias::PullUp);
+            assert_eq!(lconfig.get_drive_offset(3).unwrap(), Drive::OpenSource);
+        }
+    }
+
     mod overrides {
         use super::*;
 
@@ -89,6 +218,38 @@ mod line_config {
             assert_eq!(lconfig.get_bias_offset(GPIO).unwrap(), Bias::AsIs);
         }
 
+        #[test]
+        fn bias_bulk() {
+            let mut lconfig = LineConfig::new().unwrap();
+
+            lconfig.set_bias_overrides(Bias::PullUp, &[1, 3, 5]);
+
+            for offset in [1, 3, 5] {
+                assert!(lconfig.bias_is_overridden(offset));
+                assert_eq!(lconfig.get_bias_offset(offset).unwrap(), Bias::PullUp);
+            }
+
+            for offset in [0, 2, 4] {
+                assert!(!lconfig.bias_is_overridden(offset));
+                assert_eq!(lconfig.get_bias_offset(offset).unwrap(), Bias::AsIs);
+            }
+        }
+
+        #[test]
+        fn drive_and_edge_bulk() {
+            let mut lconfig = LineConfig::new().unwrap();
+
+            lconfig.set_drive_overrides(Drive::OpenDrain, &[0, 2]);
+            lconfig.set_edge_detection_overrides(Edge::Rising, &[4, 6]);
+
+            assert_eq!(lconfig.get_drive_offset(0).unwrap(), Drive::OpenDrain);
+            assert_eq!(lconfig.get_drive_offset(2).unwrap(), Drive::OpenDrain);
+            assert!(!lconfig.drive_is_overridden(4));
+            assert_eq!(lconfig.get_edge_detection_offset(4).unwrap(), Edge::Rising);
+            assert_eq!(lconfig.get_edge_detection_offset(6).unwrap(), Edge::Rising);
+            assert!(!lconfig.edge_detection_is_overridden(0));
+        }
+
         #[test]
         fn drive() {
             const GPIO: u32 = 3;
@@ -7,12 +7,16 @@ mod common;
 
 mod line_info {
     use libc::EINVAL;
+    use std::convert::{TryFrom, TryInto};
     use std::time::Duration;
 
     use vmm_sys_util::errno::Error as IoError;
 
     use crate::common::*;
-    use libgpiod::{Bias, Chip, Direction, Drive, Edge, Error as ChipError, EventClock};
+    use libgpiod::{
+        Bias, Chip, Config, Direction, Drive, Edge, Error as ChipError, EventClock, LineConfig,
+        LineInfoSnapshot, RequestConfig,
+    };
     use libgpiod_sys::{GPIOSIM_HOG_DIR_OUTPUT_HIGH, GPIOSIM_HOG_DIR_OUTPUT_LOW};
 
     const NGPIO: u64 = 8;
@@ -30,7 +34,7 @@ mod line_info {
                 .unwrap();
             sim.enable().unwrap();
 
-            let chip = Chip::open(sim.dev_path()).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
             let info = chip.line_info(GPIO).unwrap();
 
             assert_eq!(info.get_offset(), GPIO);
@@ -51,6 +55,25 @@ mod line_info {
                 ChipError::OperationFailed("Gpio LineInfo line-info", IoError::new(EINVAL))
             );
         }
+
+        #[test]
+        fn clone() {
+            const GPIO: u32 = 2;
+            let sim = Sim::new(Some(NGPIO), None, false).unwrap();
+            sim.set_line_name(GPIO, "two").unwrap();
+            sim.enable().unwrap();
+
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let info = chip.watch_line_info(GPIO).unwrap();
+            let copy = info.try_clone().unwrap();
+
+            assert_eq!(copy.get_offset(), info.get_offset());
+            assert_eq!(copy.get_name().unwrap(), "two");
+
+            // The copy outlives the original
+            drop(info);
+            assert_eq!(copy.get_offset(), GPIO);
+        }
     }
 
     mod properties {
@@ -69,7 +92,7 @@ mod line_info {
                 .unwrap();
             sim.enable().unwrap();
 
-            let chip = Chip::open(sim.dev_path()).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
             chip.line_info(6).unwrap();
 
             let info4 = chip.line_info(4).unwrap();
@@ -86,5 +109,129 @@ mod line_info {
             assert_eq!(info4.is_debounced(), false);
             assert_eq!(info4.get_debounce_period(), Duration::from_millis(0));
         }
+
+        #[test]
+        fn realtime_clock() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[1, 2]);
+            let mut lconfig = LineConfig::new().unwrap();
+            lconfig.set_edge_detection_default(Edge::Both);
+            lconfig.set_event_clock_override(EventClock::Monotonic, 1);
+            lconfig.set_event_clock_override(EventClock::Realtime, 2);
+            let _request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+            assert!(!chip.line_info(1).unwrap().uses_realtime_clock().unwrap());
+            assert!(chip.line_info(2).unwrap().uses_realtime_clock().unwrap());
+        }
+
+        #[test]
+        fn snapshot_diff() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let before = chip.line_info(3).unwrap().snapshot().unwrap();
+            assert!(before.diff(&before).is_empty());
+
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[3]);
+            let mut lconfig = LineConfig::new().unwrap();
+            lconfig.set_direction_default(Direction::Output);
+            lconfig.set_active_low_default(true);
+            let _request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+            let after = chip.line_info(3).unwrap().snapshot().unwrap();
+            assert_eq!(before.diff(&after), [Config::Direction, Config::ActiveLow]);
+        }
+
+        #[test]
+        fn snapshot_try_from() {
+            const LABEL: &str = "named";
+            let sim = Sim::new(Some(NGPIO), None, false).unwrap();
+            sim.set_line_name(2, LABEL).unwrap();
+            sim.enable().unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            let info = chip.line_info(1).unwrap();
+            let snapshot: LineInfoSnapshot = (&info).try_into().unwrap();
+            assert_eq!(snapshot, info.snapshot().unwrap());
+            assert_eq!(snapshot.get_offset(), 1);
+            assert_eq!(snapshot.get_name(), None);
+            assert_eq!(snapshot.get_consumer(), None);
+            assert!(!snapshot.is_used());
+
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[2]);
+            rconfig.set_consumer("consumer");
+            let mut lconfig = LineConfig::new().unwrap();
+            lconfig.set_direction_default(Direction::Output);
+            let _request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+            let snapshot = LineInfoSnapshot::try_from(&chip.line_info(2).unwrap()).unwrap();
+            assert_eq!(snapshot.get_name(), Some(LABEL));
+            assert_eq!(snapshot.get_consumer(), Some("consumer"));
+            assert!(snapshot.is_used());
+            assert_eq!(snapshot.get_direction(), Direction::Output);
+        }
+
+        #[test]
+        fn lossy_names() {
+            let sim = Sim::new(Some(NGPIO), None, false).unwrap();
+            sim.set_line_name(1, "one").unwrap();
+            sim.hog_line(1, "hog1", GPIOSIM_HOG_DIR_OUTPUT_HIGH as i32)
+                .unwrap();
+            sim.enable().unwrap();
+
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            let info = chip.line_info(1).unwrap();
+            assert_eq!(info.get_name_lossy().unwrap(), info.get_name().unwrap());
+            assert_eq!(
+                info.get_consumer_lossy().unwrap(),
+                info.get_consumer().unwrap()
+            );
+
+            // Unnamed and unused line
+            let info = chip.line_info(2).unwrap();
+            assert_eq!(info.get_name_lossy(), None);
+            assert_eq!(info.get_consumer_lossy(), None);
+        }
+
+        #[test]
+        fn summary() {
+            let sim = Sim::new(Some(NGPIO), None, false).unwrap();
+            sim.set_line_name(1, "one").unwrap();
+            sim.set_line_name(3, "three").unwrap();
+            sim.hog_line(3, "hog3", GPIOSIM_HOG_DIR_OUTPUT_HIGH as i32)
+                .unwrap();
+            sim.enable().unwrap();
+
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            let summary = chip.line_info(1).unwrap().summary();
+            assert!(summary.contains("\"one\""));
+            assert!(summary.contains("unused"));
+            assert!(summary.contains("input"));
+
+            let summary = chip.line_info(3).unwrap().summary();
+            assert!(summary.contains("\"three\""));
+            assert!(summary.contains("\"hog3\""));
+            assert!(summary.contains("output"));
+
+            let summary = chip.line_info(2).unwrap().summary();
+            assert!(summary.contains("unnamed"));
+        }
+
+        #[test]
+        fn display() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let info = chip.line_info(5).unwrap();
+
+            let output = format!("{}", info);
+            assert!(output.contains("line   5:"));
+            assert_eq!(output, info.summary());
+        }
     }
 }
@@ -7,13 +7,21 @@ mod common;
 
 mod line_request {
     use libc::{EBUSY, EINVAL};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::thread::{sleep, spawn};
+    use std::time::{Duration, Instant};
 
     use vmm_sys_util::errno::Error as IoError;
 
     use crate::common::*;
-    use libgpiod::{Bias, Direction, Error as ChipError, LineConfig};
+    use libgpiod::{
+        Bias, Chip, Direction, Drive, Edge, Error as ChipError, LineConfig, LineRequest, Offset,
+        RequestConfig,
+    };
     use libgpiod_sys::{
-        GPIOSIM_PULL_DOWN, GPIOSIM_PULL_UP, GPIOSIM_VALUE_ACTIVE, GPIOSIM_VALUE_INACTIVE,
+        gpiod_chip, gpiod_line_config, gpiod_line_request, gpiod_request_config, GPIOSIM_PULL_DOWN,
+        GPIOSIM_PULL_UP, GPIOSIM_VALUE_ACTIVE, GPIOSIM_VALUE_INACTIVE,
     };
 
     const NGPIO: u64 = 8;
@@ -56,6 +64,71 @@ mod line_request {
                 ChipError::OperationFailed("Gpio LineRequest request-lines", IoError::new(EINVAL))
             );
         }
+
+        #[test]
+        fn offset_not_requested() {
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[0, 1]));
+            config.lconfig(Some(Direction::Output), None, None, None, None);
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            assert_eq!(
+                request.set_value(99, 1).unwrap_err(),
+                ChipError::OffsetNotRequested(99)
+            );
+            assert_eq!(
+                request.get_value(2).unwrap_err(),
+                ChipError::OffsetNotRequested(2)
+            );
+            assert_eq!(
+                request.set_values_subset(&[0, 3], &[1, 1]).unwrap_err(),
+                ChipError::OffsetNotRequested(3)
+            );
+            assert_eq!(
+                request
+                    .get_values_subset(&[4, 1], &mut vec![0; 2])
+                    .unwrap_err(),
+                ChipError::OffsetNotRequested(4)
+            );
+            assert_eq!(
+                request.set_edge_detection(&[1, 5], Edge::Both).unwrap_err(),
+                ChipError::OffsetNotRequested(5)
+            );
+        }
+
+        #[test]
+        fn checked_duplicate_offsets() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let rconfig = RequestConfig::new().unwrap();
+            let lconfig = LineConfig::new().unwrap();
+            rconfig.set_offsets(&[2, 0, 0, 4]);
+
+            assert_eq!(
+                chip.request_lines_checked(&rconfig, &lconfig).unwrap_err(),
+                ChipError::DuplicateOffset(0)
+            );
+        }
+
+        #[test]
+        fn checked_out_of_bound_offsets() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let rconfig = RequestConfig::new().unwrap();
+            let lconfig = LineConfig::new().unwrap();
+            rconfig.set_offsets(&[2, 0, 8, 4]);
+
+            assert_eq!(
+                chip.request_lines_checked(&rconfig, &lconfig).unwrap_err(),
+                ChipError::InvalidValue("offset", 8)
+            );
+
+            // Valid offsets are requested
+            rconfig.set_offsets(&[2, 0, 7, 4]);
+            let request = chip.request_lines_checked(&rconfig, &lconfig).unwrap();
+            assert_eq!(request.get_offsets(), [2, 0, 7, 4]);
+        }
     }
 
     mod verify {
@@ -90,6 +163,118 @@ mod line_request {
             assert_eq!(info.get_consumer().unwrap(), "?");
         }
 
+        #[test]
+        fn num_lines() {
+            let offsets = [7, 1, 0, 6, 2];
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&offsets));
+            config.lconfig_raw();
+            config.request_lines().unwrap();
+
+            let request = config.request();
+            assert_eq!(request.len(), request.get_offsets().len());
+            assert_eq!(request.len(), request.get_num_lines() as usize);
+            assert_eq!(request.len(), offsets.len());
+        }
+
+        #[test]
+        fn sorted_offsets() {
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[7, 1, 0]));
+            config.lconfig_raw();
+            config.request_lines().unwrap();
+
+            let request = config.request();
+            assert_eq!(request.offsets_sorted(), [0, 1, 7]);
+            assert_eq!(request.get_offsets(), [7, 1, 0]);
+        }
+
+        #[test]
+        fn debug() {
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[7, 1, 0]));
+            config.lconfig_raw();
+            config.request_lines().unwrap();
+
+            assert_eq!(
+                format!("{:?}", config.request()),
+                "LineRequest { offsets: [7, 1, 0], num_lines: 3 }"
+            );
+        }
+
+        #[test]
+        fn contains_offset() {
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[2, 4]));
+            config.lconfig_raw();
+            config.request_lines().unwrap();
+
+            let request = config.request();
+            assert!(request.contains_offset(2));
+            assert!(request.contains_offset(4));
+            assert!(!request.contains_offset(3));
+        }
+
+        #[test]
+        fn raw_pointers() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[0]);
+            let lconfig = LineConfig::new().unwrap();
+            let request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+            let chip_ptr: *mut gpiod_chip = unsafe { chip.as_raw_ptr() };
+            let rconfig_ptr: *mut gpiod_request_config = unsafe { rconfig.as_raw_ptr() };
+            let lconfig_ptr: *mut gpiod_line_config = unsafe { lconfig.as_raw_ptr() };
+            let request_ptr: *mut gpiod_line_request = unsafe { request.as_raw_ptr() };
+
+            assert!(!chip_ptr.is_null());
+            assert!(!rconfig_ptr.is_null());
+            assert!(!lconfig_ptr.is_null());
+            assert!(!request_ptr.is_null());
+        }
+
+        #[test]
+        fn release() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[2, 6]);
+            let lconfig = LineConfig::new().unwrap();
+
+            let request = chip.request_lines(&rconfig, &lconfig).unwrap();
+            request.release();
+            assert!(!chip.line_info(2).unwrap().is_used());
+
+            // Lines can be requested again right away
+            let request = chip.request_lines(&rconfig, &lconfig).unwrap();
+            assert_eq!(request.get_offsets(), [2, 6]);
+        }
+
+        #[test]
+        fn raw_round_trip() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[3, 5]);
+            let lconfig = LineConfig::new().unwrap();
+            let request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+            let ptr = request.into_raw();
+            assert!(!ptr.is_null());
+
+            // The lines stay requested while owned by the raw pointer
+            assert!(chip.line_info(3).unwrap().is_used());
+
+            let request = unsafe { LineRequest::from_raw(ptr) };
+            assert_eq!(request.get_offsets(), [3, 5]);
+            assert_eq!(request.get_value(5).unwrap(), 0);
+
+            drop(request);
+            assert!(!chip.line_info(3).unwrap().is_used());
+        }
+
         #[test]
         fn read_values() {
             let offsets = [7, 1, 0, 6, 2];
@@ -158,6 +343,273 @@ mod line_request {
             assert_eq!(request.get_value(7).unwrap(), 0);
         }
 
+        #[test]
+        fn raw_value() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            sim.set_pull(2, GPIOSIM_PULL_UP as i32).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[2, 5]);
+            let mut lconfig = LineConfig::new().unwrap();
+            lconfig.set_direction_default(Direction::Input);
+            lconfig.set_active_low_override(true, 2);
+            let request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+            // Active-low line
+            assert_eq!(request.get_value(2).unwrap(), 0);
+            assert_eq!(request.get_value_raw(2).unwrap(), 1);
+
+            // Active-high line
+            assert_eq!(request.get_value(5).unwrap(), 0);
+            assert_eq!(request.get_value_raw(5).unwrap(), 0);
+        }
+
+        #[test]
+        fn typed_offsets() {
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.set_pull(&[2, 5], &[GPIOSIM_PULL_UP, GPIOSIM_PULL_DOWN]);
+            config.rconfig(Some(&[2, 5]));
+            config.lconfig(Some(Direction::Input), None, None, None, None);
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            assert_eq!(request.get_value(Offset(2)).unwrap(), 1);
+            assert_eq!(request.get_value(Offset::from(5)).unwrap(), 0);
+            assert_eq!(u32::from(Offset(5)), 5);
+
+            let info = config.chip().line_info(Offset(2)).unwrap();
+            assert_eq!(info.get_offset(), 2);
+        }
+
+        #[test]
+        fn set_direction() {
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.set_pull(&[2, 4], &[GPIOSIM_PULL_UP, GPIOSIM_PULL_UP]);
+            config.rconfig(Some(&[2, 4]));
+            config.lconfig(
+                Some(Direction::Input),
+                None,
+                None,
+                Some(Edge::Both),
+                Some(Bias::PullUp),
+            );
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            request.set_direction(4, Direction::Output).unwrap();
+            let info = config.chip().line_info(4).unwrap();
+            assert_eq!(info.get_direction().unwrap(), Direction::Output);
+            assert_eq!(info.get_edge_detection().unwrap(), Edge::None);
+            assert_eq!(info.get_bias().unwrap(), Bias::PullUp);
+            // Keeps driving the value read as an input
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_ACTIVE);
+
+            // The other line keeps its settings
+            let info = config.chip().line_info(2).unwrap();
+            assert_eq!(info.get_direction().unwrap(), Direction::Input);
+            assert_eq!(info.get_edge_detection().unwrap(), Edge::Both);
+            assert_eq!(info.get_bias().unwrap(), Bias::PullUp);
+
+            request.set_value(4, 1).unwrap();
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            request.set_value(4, 0).unwrap();
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
+
+            // Line not part of the request
+            assert_eq!(
+                request.set_direction(3, Direction::Output).unwrap_err(),
+                ChipError::OffsetNotRequested(3)
+            );
+        }
+
+        #[test]
+        fn direction() {
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&[2, 4]));
+            config.lconfig(Some(Direction::Input), None, None, None, None);
+            config.request_lines().unwrap();
+            let request = config.request();
+            assert_eq!(request.direction(4).unwrap(), Direction::Input);
+
+            let mut lconfig = LineConfig::new().unwrap();
+            lconfig.set_direction_default(Direction::Output);
+            request.reconfigure_lines(&lconfig).unwrap();
+            assert_eq!(request.direction(2).unwrap(), Direction::Output);
+            assert_eq!(request.direction(4).unwrap(), Direction::Output);
+
+            // Line not part of the request
+            assert_eq!(
+                request.direction(3).unwrap_err(),
+                ChipError::OffsetNotRequested(3)
+            );
+        }
+
+        #[test]
+        fn request_builder() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            let request = chip
+                .request_builder()
+                .unwrap()
+                .consumer("builder")
+                .offsets(&[1, 3])
+                .direction(Direction::Output)
+                .output_value(1)
+                .output_value_override(0, 3)
+                .request()
+                .unwrap();
+
+            assert_eq!(request.get_offsets(), [1, 3]);
+            assert_eq!(sim.get_value(1).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(sim.get_value(3).unwrap(), GPIOSIM_VALUE_INACTIVE);
+
+            let info = chip.line_info(1).unwrap();
+            assert_eq!(info.get_consumer().unwrap(), "builder");
+            assert_eq!(info.get_direction().unwrap(), Direction::Output);
+        }
+
+        #[test]
+        fn set_values_map() {
+            let offsets = [0, 1, 3, 4];
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&offsets));
+            config.lconfig(Some(Direction::Output), Some(0), None, None, None);
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            let values: HashMap<u32, i32> = [(4, 1), (3, 1)].iter().cloned().collect();
+            request.set_values_map(&values).unwrap();
+            assert_eq!(config.sim().get_value(0).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(1).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(3).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_ACTIVE);
+
+            // Offset not part of the request
+            let values: HashMap<u32, i32> = [(0, 1), (2, 1)].iter().cloned().collect();
+            assert_eq!(
+                request.set_values_map(&values).unwrap_err(),
+                ChipError::OffsetNotRequested(2)
+            );
+            assert_eq!(config.sim().get_value(0).unwrap(), GPIOSIM_VALUE_INACTIVE);
+        }
+
+        #[test]
+        fn set_values_iter() {
+            let offsets = [0, 1, 3, 4];
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&offsets));
+            config.lconfig(Some(Direction::Output), Some(0), None, None, None);
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            request
+                .set_values_iter([1, 4].iter().map(|offset| (*offset, 1)))
+                .unwrap();
+            assert_eq!(config.sim().get_value(0).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(1).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(3).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_ACTIVE);
+
+            // Offset not part of the request
+            assert_eq!(
+                request.set_values_iter(vec![(0, 1), (2, 1)]).unwrap_err(),
+                ChipError::OffsetNotRequested(2)
+            );
+            assert_eq!(config.sim().get_value(0).unwrap(), GPIOSIM_VALUE_INACTIVE);
+        }
+
+        #[test]
+        fn pulse() {
+            const GPIO: u32 = 3;
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[GPIO]);
+            let mut lconfig = LineConfig::new().unwrap();
+            lconfig.set_output(0);
+            let request = Arc::new(chip.request_lines(&rconfig, &lconfig).unwrap());
+
+            let thread_request = request.clone();
+            let handle = spawn(move || {
+                thread_request.pulse(
+                    GPIO,
+                    Duration::from_millis(200),
+                    Duration::from_millis(200),
+                    2,
+                )
+            });
+
+            // Sample in the middle of each half period
+            let start = Instant::now();
+            let expected = [
+                GPIOSIM_VALUE_ACTIVE,
+                GPIOSIM_VALUE_INACTIVE,
+                GPIOSIM_VALUE_ACTIVE,
+                GPIOSIM_VALUE_INACTIVE,
+            ];
+
+            for (index, value) in expected.iter().enumerate() {
+                let sample = Duration::from_millis(100 + 200 * index as u64);
+                if let Some(delay) = sample.checked_sub(start.elapsed()) {
+                    sleep(delay);
+                }
+
+                assert_eq!(sim.get_value(GPIO).unwrap(), *value);
+            }
+
+            handle.join().unwrap().unwrap();
+            assert_eq!(sim.get_value(GPIO).unwrap(), GPIOSIM_VALUE_INACTIVE);
+        }
+
+        #[test]
+        fn set_all() {
+            let offsets = [0, 1, 3, 4];
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&offsets));
+            config.lconfig(Some(Direction::Output), Some(0), None, None, None);
+            config.request_lines().unwrap();
+
+            config.request().set_all(1).unwrap();
+            for offset in offsets {
+                assert_eq!(
+                    config.sim().get_value(offset).unwrap(),
+                    GPIOSIM_VALUE_ACTIVE
+                );
+            }
+
+            config.request().set_all(0).unwrap();
+            for offset in offsets {
+                assert_eq!(
+                    config.sim().get_value(offset).unwrap(),
+                    GPIOSIM_VALUE_INACTIVE
+                );
+            }
+        }
+
+        #[test]
+        fn bitmask() {
+            let offsets = [4, 0, 3, 1];
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&offsets));
+            config.lconfig(Some(Direction::Output), Some(0), None, None, None);
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            // Bits follow the order of requested offsets
+            request.set_value_bitmask(0b1010).unwrap();
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(0).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(3).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(1).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(request.get_value_bitmask().unwrap(), 0b1010);
+
+            // Extra bits are ignored
+            request.set_value_bitmask(0xf5).unwrap();
+            assert_eq!(request.get_value_bitmask().unwrap(), 0b0101);
+        }
+
         #[test]
         fn set_output_values() {
             let offsets = [0, 1, 3, 4];
@@ -166,15 +618,15 @@ mod line_request {
             config.lconfig(Some(Direction::Output), Some(1), Some((4, 0)), None, None);
             config.request_lines().unwrap();
 
-            assert_eq!(config.sim().val(0).unwrap(), GPIOSIM_VALUE_ACTIVE);
-            assert_eq!(config.sim().val(1).unwrap(), GPIOSIM_VALUE_ACTIVE);
-            assert_eq!(config.sim().val(3).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(0).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(1).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(3).unwrap(), GPIOSIM_VALUE_ACTIVE);
 
             // Overriden
-            assert_eq!(config.sim().val(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
 
             // Default
-            assert_eq!(config.sim().val(2).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(2).unwrap(), GPIOSIM_VALUE_INACTIVE);
         }
 
         #[test]
@@ -188,34 +640,246 @@ mod line_request {
 
             // Set single value
             request.set_value(1, 1).unwrap();
-            assert_eq!(config.sim().val(0).unwrap(), GPIOSIM_VALUE_INACTIVE);
-            assert_eq!(config.sim().val(1).unwrap(), GPIOSIM_VALUE_ACTIVE);
-            assert_eq!(config.sim().val(3).unwrap(), GPIOSIM_VALUE_INACTIVE);
-            assert_eq!(config.sim().val(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(0).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(1).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(3).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
             request.set_value(1, 0).unwrap();
-            assert_eq!(config.sim().val(1).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(1).unwrap(), GPIOSIM_VALUE_INACTIVE);
 
             // Set values of subset
             request.set_values_subset(&[4, 3], &[1, 1]).unwrap();
-            assert_eq!(config.sim().val(0).unwrap(), GPIOSIM_VALUE_INACTIVE);
-            assert_eq!(config.sim().val(1).unwrap(), GPIOSIM_VALUE_INACTIVE);
-            assert_eq!(config.sim().val(3).unwrap(), GPIOSIM_VALUE_ACTIVE);
-            assert_eq!(config.sim().val(4).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(0).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(1).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(3).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_ACTIVE);
             request.set_values_subset(&[4, 3], &[0, 0]).unwrap();
-            assert_eq!(config.sim().val(3).unwrap(), GPIOSIM_VALUE_INACTIVE);
-            assert_eq!(config.sim().val(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(3).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
 
             // Set all values
             request.set_values(&[1, 0, 1, 0]).unwrap();
-            assert_eq!(config.sim().val(0).unwrap(), GPIOSIM_VALUE_ACTIVE);
-            assert_eq!(config.sim().val(1).unwrap(), GPIOSIM_VALUE_INACTIVE);
-            assert_eq!(config.sim().val(3).unwrap(), GPIOSIM_VALUE_ACTIVE);
-            assert_eq!(config.sim().val(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(0).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(1).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(3).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
             request.set_values(&[0, 0, 0, 0]).unwrap();
-            assert_eq!(config.sim().val(0).unwrap(), GPIOSIM_VALUE_INACTIVE);
-            assert_eq!(config.sim().val(1).unwrap(), GPIOSIM_VALUE_INACTIVE);
-            assert_eq!(config.sim().val(3).unwrap(), GPIOSIM_VALUE_INACTIVE);
-            assert_eq!(config.sim().val(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(0).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(1).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(3).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
+        }
+
+        #[test]
+        fn values_map() {
+            let offsets = [7, 1, 0, 6, 2];
+            let pulls = [
+                GPIOSIM_PULL_UP,
+                GPIOSIM_PULL_UP,
+                GPIOSIM_PULL_DOWN,
+                GPIOSIM_PULL_UP,
+                GPIOSIM_PULL_DOWN,
+            ];
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.set_pull(&offsets, &pulls);
+            config.rconfig(Some(&offsets));
+            config.lconfig(Some(Direction::Input), None, None, None, None);
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            let mut values = vec![0; offsets.len()];
+            request.get_values(&mut values).unwrap();
+
+            let map = request.get_values_map().unwrap();
+            assert_eq!(map.len(), offsets.len());
+            for (offset, value) in offsets.iter().zip(values) {
+                assert_eq!(map[offset], value);
+            }
+        }
+
+        #[test]
+        fn bool_values() {
+            let offsets = [7, 1, 0, 6, 2];
+            let pulls = [
+                GPIOSIM_PULL_UP,
+                GPIOSIM_PULL_UP,
+                GPIOSIM_PULL_DOWN,
+                GPIOSIM_PULL_UP,
+                GPIOSIM_PULL_DOWN,
+            ];
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.set_pull(&offsets, &pulls);
+            config.rconfig(Some(&offsets));
+            config.lconfig(Some(Direction::Input), None, None, None, None);
+            config.request_lines().unwrap();
+
+            assert_eq!(
+                config.request().get_values_bool().unwrap(),
+                [true, true, false, true, false]
+            );
+        }
+
+        #[test]
+        fn subset_bool_values() {
+            let offsets = [0, 1, 3, 4];
+            let mut config = TestConfig::new(NGPIO).unwrap();
+            config.rconfig(Some(&offsets));
+            config.lconfig(Some(Direction::Output), Some(0), None, None, None);
+            config.request_lines().unwrap();
+            let request = config.request();
+
+            request
+                .set_values_subset_bool(&[3, 4], &[true, false])
+                .unwrap();
+            assert_eq!(config.sim().get_value(3).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(
+                request.get_values_subset_bool(&[3, 4]).unwrap(),
+                [true, false]
+            );
+
+            // Size mismatch
+            assert_eq!(
+                request
+                    .set_values_subset_bool(&[3, 4], &[true])
+                    .unwrap_err(),
+                ChipError::OperationFailed(
+                    "Gpio LineRequest array size mismatch",
+                    IoError::new(EINVAL),
+                )
+            );
+        }
+
+        #[test]
+        fn request_retry() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[3]);
+            let lconfig = LineConfig::new().unwrap();
+
+            let request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+            // Busy until all the attempts fail
+            assert_eq!(
+                chip.request_lines_retry(&rconfig, &lconfig, 2, Duration::from_millis(10))
+                    .unwrap_err(),
+                ChipError::OperationFailed("Gpio LineRequest request-lines", IoError::new(EBUSY))
+            );
+
+            // Released by another thread
+            spawn(move || {
+                sleep(Duration::from_millis(100));
+                drop(request);
+            });
+
+            let request = chip
+                .request_lines_retry(&rconfig, &lconfig, 50, Duration::from_millis(20))
+                .unwrap();
+            assert_eq!(request.get_offsets(), [3]);
+        }
+
+        #[test]
+        fn read_once() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            sim.set_pull(1, GPIOSIM_PULL_UP as i32).unwrap();
+            sim.set_pull(3, GPIOSIM_PULL_DOWN as i32).unwrap();
+            sim.set_pull(6, GPIOSIM_PULL_UP as i32).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            assert_eq!(
+                chip.read_values_once("once", &[6, 3, 1]).unwrap(),
+                [1, 0, 1]
+            );
+
+            // The lines are released
+            assert!(chip.used_lines().unwrap().is_empty());
+        }
+
+        #[test]
+        fn write_once() {
+            let sim = Arc::new(Sim::new(Some(NGPIO), None, true).unwrap());
+            let chip = Arc::new(Chip::open(sim.dev_path().unwrap()).unwrap());
+
+            let thread_chip = chip.clone();
+            let writer = spawn(move || {
+                thread_chip
+                    .write_values_once("once", &[(2, 1), (5, 0)], Some(Duration::from_millis(300)))
+                    .unwrap();
+            });
+
+            // The values are held
+            sleep(Duration::from_millis(100));
+            assert_eq!(sim.get_value(2).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(sim.get_value(5).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(chip.used_lines().unwrap(), [2, 5]);
+
+            // The lines are released
+            writer.join().unwrap();
+            assert!(chip.used_lines().unwrap().is_empty());
+        }
+
+        #[test]
+        fn output_safe() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            let request = chip.request_output_safe("safe", &[(2, 1), (4, 0)]).unwrap();
+
+            // Initial values are applied by the request itself
+            assert_eq!(sim.get_value(2).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(sim.get_value(4).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(request.get_offsets(), [2, 4]);
+
+            let info = chip.line_info(2).unwrap();
+            assert_eq!(info.get_consumer().unwrap(), "safe");
+            assert_eq!(info.get_direction().unwrap(), Direction::Output);
+            assert_eq!(info.get_drive().unwrap(), Drive::PushPull);
+            drop(request);
+
+            // Invalid values are rejected before requesting the lines
+            assert_eq!(
+                chip.request_output_safe("safe", &[(2, 1), (4, 2)]).err(),
+                Some(ChipError::InvalidValue("output", 2))
+            );
+            assert!(!chip.line_info(2).unwrap().is_used());
+        }
+
+        #[test]
+        fn configure_outputs() {
+            let sim = Sim::new(Some(NGPIO), None, true).unwrap();
+            sim.set_pull(3, GPIOSIM_PULL_UP as i32).unwrap();
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+
+            let rconfig = RequestConfig::new().unwrap();
+            rconfig.set_offsets(&[1, 3]);
+            let mut lconfig = LineConfig::new().unwrap();
+            lconfig
+                .configure_outputs(&[(1, 0), (3, 1)], Drive::OpenDrain)
+                .unwrap();
+            let _request = chip.request_lines(&rconfig, &lconfig).unwrap();
+
+            let info = chip.line_info(1).unwrap();
+            assert_eq!(info.get_direction().unwrap(), Direction::Output);
+            assert_eq!(info.get_drive().unwrap(), Drive::OpenDrain);
+            assert_eq!(
+                chip.line_info(3).unwrap().get_drive().unwrap(),
+                Drive::OpenDrain
+            );
+
+            // Open-drain lines are only driven low
+            assert_eq!(sim.get_value(1).unwrap(), GPIOSIM_VALUE_INACTIVE);
+            assert_eq!(sim.get_value(3).unwrap(), GPIOSIM_VALUE_ACTIVE);
+
+            // Invalid values leave the config untouched
+            let mut lconfig = LineConfig::new().unwrap();
+            assert_eq!(
+                lconfig
+                    .configure_outputs(&[(1, 0), (3, -1)], Drive::OpenDrain)
+                    .unwrap_err(),
+                ChipError::InvalidValue("output", -1i32 as u32)
+            );
+            assert_eq!(lconfig.get_direction_default().unwrap(), Direction::AsIs);
         }
 
         #[test]
@@ -228,7 +892,7 @@ mod line_request {
             config.request();
 
             // Set single value
-            assert_eq!(config.sim().val(3).unwrap(), GPIOSIM_VALUE_ACTIVE);
+            assert_eq!(config.sim().get_value(3).unwrap(), GPIOSIM_VALUE_ACTIVE);
         }
     }
 }
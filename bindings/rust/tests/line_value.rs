@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+mod line_value {
+    use std::convert::TryFrom;
+
+    use libgpiod::{Error as ChipError, LineValue};
+
+    #[test]
+    fn conversions() {
+        assert_eq!(LineValue::from(true), LineValue::Active);
+        assert_eq!(LineValue::from(false), LineValue::InActive);
+        assert!(bool::from(LineValue::Active));
+        assert!(!bool::from(LineValue::InActive));
+        assert_eq!(i32::from(LineValue::Active), 1);
+        assert_eq!(i32::from(LineValue::InActive), 0);
+
+        assert_eq!(LineValue::Active, true);
+        assert_eq!(LineValue::InActive, false);
+        assert_eq!(!LineValue::Active, LineValue::InActive);
+        assert_eq!(!LineValue::InActive, LineValue::Active);
+
+        assert_eq!(LineValue::Active.to_string(), "1");
+        assert_eq!(LineValue::InActive.to_string(), "0");
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(LineValue::try_from(0).unwrap(), LineValue::InActive);
+        assert_eq!(LineValue::try_from(1).unwrap(), LineValue::Active);
+        assert_eq!(
+            LineValue::try_from(2).unwrap_err(),
+            ChipError::InvalidValue("line", 2)
+        );
+    }
+}
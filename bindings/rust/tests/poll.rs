@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+mod poll {
+    use libc::{pipe, write};
+    use std::os::unix::io::{AsFd, FromRawFd, OwnedFd};
+    use std::time::Duration;
+
+    use libgpiod::wait_readable;
+
+    #[test]
+    fn pipe_readable() {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { pipe(fds.as_mut_ptr()) }, 0);
+        let (read_fd, write_fd) =
+            unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+
+        // Nothing written yet
+        assert!(!wait_readable(read_fd.as_fd(), Some(Duration::from_millis(100))).unwrap());
+
+        let data = [1u8];
+        assert_eq!(
+            unsafe { write(fds[1], data.as_ptr() as *const _, data.len()) },
+            1
+        );
+
+        assert!(wait_readable(read_fd.as_fd(), Some(Duration::from_millis(100))).unwrap());
+        assert!(wait_readable(read_fd.as_fd(), None).unwrap());
+        drop(write_fd);
+    }
+}
@@ -38,5 +38,32 @@ mod request_config {
             assert_eq!(rconfig.get_event_buffer_size(), 64);
             assert_eq!(rconfig.get_consumer().unwrap(), CONSUMER);
         }
+
+        #[test]
+        fn offsets_iter() {
+            let rconfig = RequestConfig::new().unwrap();
+
+            rconfig.set_offsets_iter(0..4);
+            assert_eq!(rconfig.get_offsets(), [0, 1, 2, 3]);
+
+            rconfig.set_offsets_iter((0..8).filter(|offset| offset % 2 == 1));
+            assert_eq!(rconfig.get_offsets(), [1, 3, 5, 7]);
+        }
+
+        #[test]
+        fn push_offsets() {
+            let rconfig = RequestConfig::new().unwrap();
+
+            rconfig.push_offset(4);
+            rconfig.push_offset(2);
+            rconfig.push_offset(6);
+            assert_eq!(rconfig.get_offsets(), [4, 2, 6]);
+
+            rconfig.clear_offsets();
+            assert!(rconfig.get_offsets().is_empty());
+
+            rconfig.push_offset(1);
+            assert_eq!(rconfig.get_offsets(), [1]);
+        }
     }
 }
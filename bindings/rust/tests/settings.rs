@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+mod settings {
+    mod direction {
+        use libgpiod::Direction;
+
+        #[test]
+        fn predicates() {
+            assert!(Direction::Input.is_input());
+            assert!(!Direction::Input.is_output());
+            assert!(!Direction::Input.is_as_is());
+
+            assert!(!Direction::Output.is_input());
+            assert!(Direction::Output.is_output());
+            assert!(!Direction::Output.is_as_is());
+
+            assert!(!Direction::AsIs.is_input());
+            assert!(!Direction::AsIs.is_output());
+            assert!(Direction::AsIs.is_as_is());
+        }
+    }
+
+    mod edge {
+        use std::convert::TryFrom;
+
+        use libgpiod::{Edge, Error as ChipError, LineEdgeEvent};
+        use libgpiod_sys::{GPIOD_LINE_EDGE_BOTH, GPIOD_LINE_EDGE_NONE};
+
+        #[test]
+        fn from_edge_event() {
+            for event in [LineEdgeEvent::Rising, LineEdgeEvent::Falling] {
+                let edge = Edge::from(event);
+                assert_eq!(LineEdgeEvent::try_from(edge).unwrap(), event);
+            }
+
+            assert_eq!(Edge::from(LineEdgeEvent::Rising), Edge::Rising);
+            assert_eq!(Edge::from(LineEdgeEvent::Falling), Edge::Falling);
+        }
+
+        #[test]
+        fn to_edge_event() {
+            assert_eq!(
+                LineEdgeEvent::try_from(Edge::None).unwrap_err(),
+                ChipError::InvalidValue("edge event", GPIOD_LINE_EDGE_NONE)
+            );
+            assert_eq!(
+                LineEdgeEvent::try_from(Edge::Both).unwrap_err(),
+                ChipError::InvalidValue("edge event", GPIOD_LINE_EDGE_BOTH)
+            );
+        }
+
+        #[test]
+        fn flags() {
+            let edges = [
+                (false, false, Edge::None),
+                (true, false, Edge::Rising),
+                (false, true, Edge::Falling),
+                (true, true, Edge::Both),
+            ];
+
+            for (rising, falling, edge) in edges {
+                assert_eq!(Edge::from_flags(rising, falling), edge);
+                assert_eq!(edge.flags(), (rising, falling));
+            }
+        }
+    }
+
+    mod event_clock {
+        use std::collections::HashMap;
+
+        use libgpiod::EventClock;
+
+        #[test]
+        fn hash() {
+            let mut clocks = HashMap::new();
+
+            for clock in [
+                EventClock::Monotonic,
+                EventClock::Realtime,
+                EventClock::Monotonic,
+            ] {
+                *clocks.entry(clock).or_insert(0) += 1;
+            }
+
+            assert_eq!(clocks.len(), 2);
+            assert_eq!(clocks[&EventClock::Monotonic], 2);
+            assert_eq!(clocks[&EventClock::Realtime], 1);
+        }
+    }
+}
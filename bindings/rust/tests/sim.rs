@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+//
+// Copyright 2022 Linaro Ltd. All Rights Reserved.
+//     Viresh Kumar <viresh.kumar@linaro.org>
+
+mod common;
+
+mod sim {
+    use crate::common::*;
+    use libgpiod::Chip;
+
+    mod banks {
+        use super::*;
+
+        #[test]
+        fn multiple() {
+            let mut sim = Sim::new(Some(8), Some("first"), false).unwrap();
+            let bank = sim.add_bank(Some(4), Some("second")).unwrap();
+            sim.enable().unwrap();
+
+            let chip = Chip::open(sim.dev_path().unwrap()).unwrap();
+            assert_eq!(chip.get_name().unwrap(), sim.chip_name().unwrap());
+            assert_eq!(chip.get_label().unwrap(), "first");
+            assert_eq!(chip.get_num_lines(), 8);
+
+            let chip2 = Chip::open(bank.dev_path().unwrap()).unwrap();
+            assert_eq!(chip2.get_name().unwrap(), bank.chip_name().unwrap());
+            assert_eq!(chip2.get_label().unwrap(), "second");
+            assert_eq!(chip2.get_num_lines(), 4);
+
+            assert_ne!(chip, chip2);
+        }
+    }
+}